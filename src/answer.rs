@@ -0,0 +1,174 @@
+use crate::ocr;
+use std::fmt::Display;
+
+/// The answer to one part of a puzzle.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Answer {
+    Int(i64),
+    /// Integers which do not fit in an `i64`.
+    BigInt(i128),
+    Text(String),
+    /// Rows of a picture, such as a CRT screen, without line endings.
+    Grid(Vec<String>),
+    /// A picture of letters along with the text read from it.
+    Letters {
+        text: String,
+        rows: Vec<String>,
+    },
+}
+
+impl Answer {
+    pub fn grid(text: &str) -> Self {
+        Answer::Grid(text.lines().map(|line| line.to_string()).collect())
+    }
+
+    /// The text drawn in a picture, or the picture itself as a grid when it
+    /// can not be read.
+    pub fn letters(rows: Vec<String>) -> Self {
+        match ocr(&rows) {
+            Some(text) => Answer::Letters { text, rows },
+            None => Answer::Grid(rows),
+        }
+    }
+
+    /// Checks this answer against its text form, as recorded in an answers
+    /// file, ignoring surrounding whitespace.
+    pub fn matches(&self, expected: &str) -> bool {
+        match self {
+            Answer::Int(value) => expected.trim().parse::<i64>() == Ok(*value),
+            Answer::BigInt(value) => expected.trim().parse::<i128>() == Ok(*value),
+            Answer::Text(value) => expected.trim() == value.trim(),
+            Answer::Grid(rows) => grid_matches(rows, expected),
+            // older answers may record the picture rather than the text
+            Answer::Letters { text, rows } => {
+                expected.trim() == text || grid_matches(rows, expected)
+            }
+        }
+    }
+}
+
+fn grid_matches(rows: &[String], expected: &str) -> bool {
+    let expected = expected.trim_end().lines().map(str::trim_end);
+    expected.eq(rows.iter().map(|row| row.trim_end()))
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Int(value) => write!(f, "{value}"),
+            Answer::BigInt(value) => write!(f, "{value}"),
+            Answer::Text(value) => f.write_str(value),
+            Answer::Grid(rows) => f.write_str(&rows.join("\n")),
+            Answer::Letters { text, .. } => f.write_str(text),
+        }
+    }
+}
+
+macro_rules! from_int {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(value: $t) -> Self {
+                    Answer::Int(value.into())
+                }
+            }
+        )*
+    };
+}
+
+from_int!(i8, i16, i32, i64, u8, u16, u32);
+
+macro_rules! from_big_int {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(value: $t) -> Self {
+                    match i64::try_from(value) {
+                        Ok(value) => Answer::Int(value),
+                        Err(_) => Answer::BigInt(value as i128),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+from_big_int!(isize, usize, u64, i128);
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Text(value)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Answer::Text(value.to_string())
+    }
+}
+
+impl From<char> for Answer {
+    fn from(value: char) -> Self {
+        Answer::Text(value.to_string())
+    }
+}
+
+impl From<Vec<String>> for Answer {
+    fn from(rows: Vec<String>) -> Self {
+        Answer::Grid(rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from() {
+        assert_eq!(Answer::from(24000), Answer::Int(24000));
+        assert_eq!(Answer::from(7usize), Answer::Int(7));
+        assert_eq!(Answer::from(u64::MAX), Answer::BigInt(u64::MAX as i128));
+        assert_eq!(Answer::from("CMZ"), Answer::Text("CMZ".to_string()));
+        assert_eq!(
+            Answer::grid("#.\n.#\n"),
+            Answer::Grid(vec!["#.".to_string(), ".#".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Answer::from(-3).to_string(), "-3");
+        assert_eq!(Answer::from(u64::MAX).to_string(), "18446744073709551615");
+        assert_eq!(Answer::grid("#.\n.#\n").to_string(), "#.\n.#");
+    }
+
+    #[test]
+    fn test_matches() {
+        assert!(Answer::from(24000).matches("24000\n"));
+        assert!(!Answer::from(24000).matches("2400"));
+        assert!(!Answer::from(24000).matches("CMZ"));
+        assert!(Answer::from(u64::MAX).matches(" 18446744073709551615"));
+        assert!(Answer::from("CMZ").matches("CMZ\n"));
+        assert!(Answer::grid("#.\n.#\n").matches("#.\n.#\n\n"));
+        assert!(!Answer::grid("#.\n.#\n").matches("#.\n"));
+    }
+
+    #[test]
+    fn test_letters() {
+        let picture = ".##.\n#..#\n#..#\n####\n#..#\n#..#\n";
+        let letters = Answer::letters(picture.lines().map(str::to_string).collect());
+        assert_eq!(
+            letters,
+            Answer::Letters {
+                text: "A".to_string(),
+                rows: picture.lines().map(str::to_string).collect()
+            }
+        );
+        assert_eq!(letters.to_string(), "A");
+        assert!(letters.matches("A\n"));
+        assert!(letters.matches(picture));
+        assert!(!letters.matches("B"));
+        let unreadable = vec!["#.".to_string(), ".#".to_string()];
+        assert_eq!(Answer::letters(unreadable), Answer::grid("#.\n.#"));
+    }
+}
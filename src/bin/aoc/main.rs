@@ -0,0 +1,235 @@
+mod scaffold;
+
+use advent_of_code::{Format, Runner, Status, Timing};
+
+struct Day {
+    name: &'static str,
+    run_parts: fn(&Runner),
+}
+
+impl Day {
+    fn number(&self) -> usize {
+        self.name[3..].parse::<usize>().unwrap()
+    }
+}
+
+macro_rules! days {
+    ($($day:ident => $path:literal),* $(,)?) => {
+        $(
+            #[allow(dead_code)]
+            #[path = $path]
+            mod $day;
+        )*
+
+        const DAYS: &[Day] = &[$(Day { name: stringify!($day), run_parts: $day::run_parts }),*];
+    };
+}
+
+days! {
+    day01 => "../day01/main.rs",
+    day02 => "../day02/main.rs",
+    day03 => "../day03/main.rs",
+    day04 => "../day04/main.rs",
+    day05 => "../day05/main.rs",
+    day06 => "../day06/main.rs",
+    day07 => "../day07/main.rs",
+    day08 => "../day08/main.rs",
+    day09 => "../day09/main.rs",
+    day10 => "../day10/main.rs",
+    day11 => "../day11/main.rs",
+    day12 => "../day12/main.rs",
+    day13 => "../day13/main.rs",
+    day14 => "../day14/main.rs",
+    day15 => "../day15/main.rs",
+    day16 => "../day16/main.rs",
+    day17 => "../day17/main.rs",
+    day18 => "../day18/main.rs",
+    day19 => "../day19/main.rs",
+    day20 => "../day20/main.rs",
+    day21 => "../day21/main.rs",
+    day22 => "../day22/main.rs",
+    day23 => "../day23/main.rs",
+    day24 => "../day24/main.rs",
+    day25 => "../day25/main.rs",
+}
+
+/// Accepts `all`, a single day number, or a range such as `10..15` or `10..=15`.
+fn select(selection: &str) -> Option<Vec<&'static Day>> {
+    let range = if selection == "all" {
+        1..=usize::MAX
+    } else if let Some((start, end)) = selection.split_once("..=") {
+        start.parse::<usize>().ok()?..=end.parse::<usize>().ok()?
+    } else if let Some((start, end)) = selection.split_once("..") {
+        start.parse::<usize>().ok()?..=end.parse::<usize>().ok()?.checked_sub(1)?
+    } else {
+        let day = selection.parse::<usize>().ok()?;
+        day..=day
+    };
+    let days = DAYS
+        .iter()
+        .filter(|day| range.contains(&day.number()))
+        .collect::<Vec<_>>();
+    (!days.is_empty()).then_some(days)
+}
+
+/// Options which replace a day's own input or answers, so they only make
+/// sense when a single day is selected.
+const SINGLE_DAY_OPTIONS: &[&str] = &["--input", "-i", "--answers"];
+
+fn single_day_option(args: &[String]) -> Option<&'static str> {
+    args.iter().find_map(|arg| {
+        SINGLE_DAY_OPTIONS.iter().copied().find(|option| {
+            arg.strip_prefix(option)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('='))
+        })
+    })
+}
+
+fn usage() -> ! {
+    eprintln!("usage: aoc [list | all | DAY | START..END | START..=END] [PART...]");
+    eprintln!("           [--input PATH] [--bench[=RUNS]] [--format text|json|csv]");
+    eprintln!("           [--answers PATH] [--trace | --play] [--fps=RATE] [--steps=COUNT]");
+    eprintln!("           [--images DIR] [--image-format png|ppm] [--parallel]");
+    eprintln!("       aoc new DAY [PART1_EXAMPLE_ANSWER [PART2_EXAMPLE_ANSWER]]");
+    std::process::exit(2)
+}
+
+/// Runs every day on its own thread, printing their results in day order
+/// once all of them are done.
+fn run_parallel(days: &[&'static Day], runners: Vec<Runner>) -> Vec<Runner> {
+    if let Some(runner) = runners.first() {
+        runner.format().print_header();
+    }
+    let runners = std::thread::scope(|scope| {
+        let workers = days
+            .iter()
+            .zip(runners)
+            .map(|(day, runner)| {
+                scope.spawn(move || {
+                    let runner = runner.buffered();
+                    (day.run_parts)(&runner);
+                    runner
+                })
+            })
+            .collect::<Vec<_>>();
+        workers
+            .into_iter()
+            .map(|worker| {
+                worker
+                    .join()
+                    .unwrap_or_else(|e| std::panic::resume_unwind(e))
+            })
+            .collect::<Vec<_>>()
+    });
+    for runner in &runners {
+        print!("{}", runner.take_output());
+    }
+    runners
+}
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let selection = args.next();
+    let args = args.collect::<Vec<_>>();
+    match selection.as_deref() {
+        None | Some("list") => {
+            for day in DAYS {
+                (day.run_parts)(&Runner::listing(day.name));
+            }
+        }
+        Some("-h" | "--help") => usage(),
+        Some("new") => {
+            let day = args
+                .first()
+                .and_then(|day| day.parse::<usize>().ok())
+                .filter(|day| (1..=25).contains(day))
+                .unwrap_or_else(|| usage());
+            let root = std::path::Path::new(env!("CARGO_MANIFEST_DIR"));
+            match scaffold::create_day(root, day, &args[1..]) {
+                Ok(dir) => println!("created {}", dir.display()),
+                Err(e) => {
+                    eprintln!("failed to create day {day}: {e}");
+                    std::process::exit(1);
+                }
+            }
+        }
+        Some(selection) => {
+            let days = select(selection).unwrap_or_else(|| usage());
+            if let (true, Some(option)) = (days.len() > 1, single_day_option(&args)) {
+                eprintln!(
+                    "{option} needs a single day, but {selection} selects {}",
+                    days.len()
+                );
+                std::process::exit(2);
+            }
+            let runners = days
+                .iter()
+                .map(|day| Runner::from_args(day.name, args.clone()))
+                .collect::<Vec<_>>();
+            let format = runners[0].format();
+            let runners = if runners[0].parallel() {
+                run_parallel(&days, runners)
+            } else {
+                for (day, runner) in days.iter().zip(&runners) {
+                    (day.run_parts)(runner);
+                }
+                runners
+            };
+            let mut timings = Vec::new();
+            let mut statuses = Vec::new();
+            for runner in runners {
+                statuses.extend(runner.statuses());
+                timings.extend(runner.into_timings());
+            }
+            if !timings.is_empty() && format == Format::Text {
+                println!();
+                Timing::print_summary(&timings);
+            }
+            let count = |status| statuses.iter().filter(|&&s| s == status).count();
+            let (failed, errors) = (count(Status::Fail), count(Status::Error));
+            if format == Format::Text {
+                println!();
+                println!(
+                    "{} passed, {failed} failed, {} unknown, {errors} errors",
+                    count(Status::Pass),
+                    count(Status::Unknown)
+                );
+            }
+            if failed + errors > 0 {
+                std::process::exit(1);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numbers(selection: &str) -> Option<Vec<usize>> {
+        select(selection).map(|days| days.iter().map(|day| day.number()).collect())
+    }
+
+    #[test]
+    fn test_select() {
+        assert_eq!(numbers("all").map(|days| days.len()), Some(DAYS.len()));
+        assert_eq!(numbers("14"), Some(vec![14]));
+        assert_eq!(numbers("10..13"), Some(vec![10, 11, 12]));
+        assert_eq!(numbers("10..=13"), Some(vec![10, 11, 12, 13]));
+        assert_eq!(numbers("99"), None);
+        assert_eq!(numbers("part1"), None);
+    }
+
+    #[test]
+    fn test_single_day_option() {
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        assert_eq!(single_day_option(&args(&["part1", "--bench"])), None);
+        assert_eq!(single_day_option(&args(&["--input", "-"])), Some("--input"));
+        assert_eq!(single_day_option(&args(&["-i=x.txt"])), Some("-i"));
+        assert_eq!(
+            single_day_option(&args(&["--answers=a.txt"])),
+            Some("--answers")
+        );
+        assert_eq!(single_day_option(&args(&["--inputs"])), None);
+    }
+}
@@ -0,0 +1,135 @@
+use std::{
+    fs,
+    io::{self, ErrorKind},
+    path::{Path, PathBuf},
+};
+
+const TEMPLATE_FILES: [&str; 3] = ["example.txt", "input.txt", "answers.txt"];
+const DAYS_START: &str = "days! {\n";
+
+/// Creates `src/bin/dayNN` under `root` from `src/bin/template`, with the
+/// example expectations of each part filled in, and registers it with `aoc`.
+pub fn create_day(root: &Path, day: usize, expected: &[String]) -> io::Result<PathBuf> {
+    let name = format!("day{day:02}");
+    let bin = root.join("src").join("bin");
+    let dir = bin.join(&name);
+    if dir.exists() {
+        return Err(io::Error::new(
+            ErrorKind::AlreadyExists,
+            format!("{} already exists", dir.display()),
+        ));
+    }
+    let template = bin.join("template");
+    let main = expected.iter().enumerate().fold(
+        fs::read_to_string(template.join("main.rs"))?,
+        |main, (index, expected)| {
+            let part = index + 1;
+            main.replace(
+                &format!("verify!(part{part}, input, 0);"),
+                &format!("verify!(part{part}, input, {});", literal(expected)),
+            )
+        },
+    );
+    let aoc = bin.join("aoc").join("main.rs");
+    let registry = register(&fs::read_to_string(&aoc)?, &name)?;
+
+    fs::create_dir(&dir)?;
+    fs::write(dir.join("main.rs"), main)?;
+    for file in TEMPLATE_FILES {
+        fs::copy(template.join(file), dir.join(file))?;
+    }
+    fs::write(aoc, registry)?;
+    Ok(dir)
+}
+
+/// The Rust literal for an expected answer. Integers stay integers, with
+/// a suffix when they don't fit the default `i32`, so that they compare
+/// equal to the `Answer` which the part returns.
+fn literal(expected: &str) -> String {
+    match expected.parse::<i128>() {
+        Ok(value) if value.to_string() != expected => format!("{expected:?}"),
+        Ok(value) if i32::try_from(value).is_ok() => expected.to_string(),
+        Ok(value) if i64::try_from(value).is_ok() => format!("{expected}i64"),
+        Ok(_) => format!("{expected}i128"),
+        Err(_) => format!("{expected:?}"),
+    }
+}
+
+/// Adds `name` to the `days!` registry, keeping the entries sorted.
+fn register(source: &str, name: &str) -> io::Result<String> {
+    let invalid = || io::Error::new(ErrorKind::InvalidData, "days! registry not found");
+    let start = source.find(DAYS_START).ok_or_else(invalid)? + DAYS_START.len();
+    let end = start + source[start..].find("}\n").ok_or_else(invalid)?;
+    let entry = format!("    {name} => \"../{name}/main.rs\",\n");
+    let offset = source[start..end]
+        .split_inclusive('\n')
+        .take_while(|line| line.as_bytes() < entry.as_bytes())
+        .map(|line| line.len())
+        .sum::<usize>();
+    let mut source = source.to_string();
+    source.insert_str(start + offset, &entry);
+    Ok(source)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_literal() {
+        assert_eq!(literal("24000"), "24000");
+        assert_eq!(literal("2713310158"), "2713310158i64");
+        assert_eq!(literal("18446744073709551616"), "18446744073709551616i128");
+        assert_eq!(literal("CMZ"), "\"CMZ\"");
+        assert_eq!(literal("007"), "\"007\"");
+    }
+
+    #[test]
+    fn test_register() {
+        let source =
+            "days! {\n    day01 => \"../day01/main.rs\",\n    day03 => \"../day03/main.rs\",\n}\n";
+        assert_eq!(
+            register(source, "day02").unwrap(),
+            "days! {\n    day01 => \"../day01/main.rs\",\n    day02 => \"../day02/main.rs\",\n    day03 => \"../day03/main.rs\",\n}\n"
+        );
+        assert!(register(source, "day04")
+            .unwrap()
+            .ends_with("    day04 => \"../day04/main.rs\",\n}\n"));
+    }
+
+    #[test]
+    fn test_create_day() {
+        let root = std::env::temp_dir().join(format!("aoc-scaffold-{}", std::process::id()));
+        let bin = root.join("src").join("bin");
+        let manifest = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("src")
+            .join("bin");
+        fs::create_dir_all(bin.join("template")).unwrap();
+        fs::create_dir_all(bin.join("aoc")).unwrap();
+        for file in TEMPLATE_FILES.iter().chain(&["main.rs"]) {
+            fs::copy(
+                manifest.join("template").join(file),
+                bin.join("template").join(file),
+            )
+            .unwrap();
+        }
+        fs::copy(
+            manifest.join("aoc").join("main.rs"),
+            bin.join("aoc").join("main.rs"),
+        )
+        .unwrap();
+
+        let dir = create_day(&root, 30, &["24000".to_string(), "CMZ".to_string()]).unwrap();
+        assert_eq!(dir, bin.join("day30"));
+        let main = fs::read_to_string(dir.join("main.rs")).unwrap();
+        assert!(main.contains("verify!(part1, input, 24000);"));
+        assert!(main.contains("verify!(part2, input, \"CMZ\");"));
+        assert!(dir.join("example.txt").exists());
+        let aoc = fs::read_to_string(bin.join("aoc").join("main.rs")).unwrap();
+        assert!(aoc.contains("    day30 => \"../day30/main.rs\",\n}\n"));
+
+        let error = create_day(&root, 30, &[]).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::AlreadyExists);
+        fs::remove_dir_all(root).unwrap();
+    }
+}
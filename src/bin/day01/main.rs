@@ -1,47 +1,265 @@
-use advent_of_code::{create_runner, named, Named, Runner};
-use itertools::Itertools;
-use std::str::Lines;
-
-fn part1(input: Lines) -> String {
-    input
-        .map(|line| line.parse::<i32>().ok())
-        .group_by(|x| x.is_some())
-        .into_iter()
-        .filter_map(|(_, grp)| grp.while_some().sum1())
-        .max()
-        .unwrap_or(0)
-        .to_string()
-}
-
-fn part2(input: Lines) -> String {
-    input
-        .map(|line| line.parse::<i32>().ok())
-        .group_by(|x| x.is_some())
-        .into_iter()
-        .filter_map(|(_, grp)| grp.while_some().sum1())
-        .map(|x: i32| -x)
-        .k_smallest(3)
+use advent_of_code::{create_runner, named, sections, Answer, LineError, Named, Result, Runner};
+use std::{cmp::Reverse, collections::BinaryHeap, str::Lines};
+
+fn parse_calories(line: &str) -> Result<i32, LineError> {
+    line.parse::<i32>()
+        .map_err(|_| LineError::new(format!("expected calories, got {line:?}")))
+}
+
+/// The food carried by one elf, numbered from 1 in input order.
+#[derive(Clone, PartialEq, Eq, Debug)]
+struct Elf {
+    number: usize,
+    calories: Vec<i32>,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+struct Stats {
+    count: usize,
+    min: i32,
+    max: i32,
+    mean: f64,
+}
+
+impl Elf {
+    fn total(&self) -> i32 {
+        self.calories.iter().sum()
+    }
+
+    fn stats(&self) -> Stats {
+        Stats {
+            count: self.calories.len(),
+            min: self.calories.iter().copied().min().unwrap_or(0),
+            max: self.calories.iter().copied().max().unwrap_or(0),
+            mean: self.total() as f64 / self.calories.len().max(1) as f64,
+        }
+    }
+}
+
+/// Every elf's food, one group of lines per elf.
+struct Inventory {
+    elves: Vec<Elf>,
+}
+
+impl Inventory {
+    fn parse(input: Lines) -> Result<Self> {
+        let elves = sections(input)
+            .iter()
+            .enumerate()
+            .map(|(index, section)| {
+                Ok(Elf {
+                    number: index + 1,
+                    calories: section.parse_lines(parse_calories)?,
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { elves })
+    }
+
+    /// The elves by total, most first, with ties in input order.
+    fn ranked(&self) -> Vec<&Elf> {
+        let mut elves = self.elves.iter().collect::<Vec<_>>();
+        elves.sort_by_key(|elf| Reverse(elf.total()));
+        elves
+    }
+
+    /// The `k` elves carrying the most, where ties go to the earlier elf.
+    fn top(&self, k: usize) -> Vec<&Elf> {
+        let mut elves = self.ranked();
+        elves.truncate(k);
+        elves
+    }
+
+    /// Like `top`, along with any other elves carrying as much as the last.
+    fn top_with_ties(&self, k: usize) -> Vec<&Elf> {
+        let elves = self.ranked();
+        let Some(last) = k.checked_sub(1).and_then(|i| elves.get(i)) else {
+            return elves.into_iter().take(k).collect();
+        };
+        let least = last.total();
+        elves
+            .into_iter()
+            .take_while(|elf| elf.total() >= least)
+            .collect()
+    }
+}
+
+/// The `k` largest totals while reading one line at a time, so memory stays
+/// bounded however many elves there are. Ties go to the earlier elf.
+struct TopK {
+    k: usize,
+    /// `(total, elf)` pairs, the one to drop next on top.
+    kept: BinaryHeap<Reverse<(i32, Reverse<usize>)>>,
+    elves: usize,
+    current: Option<i32>,
+}
+
+impl TopK {
+    fn new(k: usize) -> Self {
+        Self {
+            k,
+            kept: BinaryHeap::with_capacity(k + 1),
+            elves: 0,
+            current: None,
+        }
+    }
+
+    fn push_line(&mut self, line: &str) -> Result<(), LineError> {
+        if line.trim().is_empty() {
+            self.end_elf();
+        } else {
+            let calories = parse_calories(line)?;
+            *self.current.get_or_insert(0) += calories;
+        }
+        Ok(())
+    }
+
+    fn end_elf(&mut self) {
+        if let Some(total) = self.current.take() {
+            self.elves += 1;
+            self.kept.push(Reverse((total, Reverse(self.elves))));
+            if self.kept.len() > self.k {
+                self.kept.pop();
+            }
+        }
+    }
+
+    /// `(elf, total)` for the elves kept, most first.
+    fn finish(mut self) -> Vec<(usize, i32)> {
+        self.end_elf();
+        let mut kept = self
+            .kept
+            .into_iter()
+            .map(|Reverse((total, Reverse(elf)))| (elf, total))
+            .collect::<Vec<_>>();
+        kept.sort_by_key(|&(elf, total)| (Reverse(total), elf));
+        kept
+    }
+}
+
+fn stream_top<'a>(input: impl IntoIterator<Item = &'a str>, k: usize) -> Result<Vec<(usize, i32)>> {
+    let mut top = TopK::new(k);
+    for (index, line) in input.into_iter().enumerate() {
+        top.push_line(line)
+            .map_err(|error| error.on_line(index + 1))?;
+    }
+    Ok(top.finish())
+}
+
+fn part1(input: Lines) -> Result<Answer> {
+    let inventory = Inventory::parse(input)?;
+    Ok(inventory.top(1).first().map_or(0, |elf| elf.total()).into())
+}
+
+fn part2(input: Lines) -> Result<Answer> {
+    let inventory = Inventory::parse(input)?;
+    Ok(inventory
+        .top(3)
+        .iter()
+        .map(|elf| elf.total())
         .sum::<i32>()
-        .abs()
-        .to_string()
+        .into())
 }
 
-fn main() {
+/// Same as part 2, without keeping the whole inventory.
+fn streaming(input: Lines) -> Result<Answer> {
+    Ok(stream_top(input, 3)?
+        .iter()
+        .map(|&(_, total)| total)
+        .sum::<i32>()
+        .into())
+}
+
+/// The top three elves, and any tied with the third, with what they carry.
+fn report(input: Lines) -> Result<Answer> {
+    let inventory = Inventory::parse(input)?;
+    Ok(inventory
+        .top_with_ties(3)
+        .iter()
+        .map(|elf| {
+            let Stats {
+                count,
+                min,
+                max,
+                mean,
+            } = elf.stats();
+            let items = if count == 1 { "item" } else { "items" };
+            format!(
+                "elf {}: {} calories in {count} {items} (min {min}, max {max}, mean {mean:.1})",
+                elf.number,
+                elf.total()
+            )
+        })
+        .collect::<Vec<_>>()
+        .into())
+}
+
+pub fn run_parts(runner: &Runner) {
     let input = include_str!("input.txt");
-    let runner: &Runner = create_runner!();
+    runner.known_answers(include_str!("answers.txt"));
     runner.run(named!(part1), input);
     runner.run(named!(part2), input);
+    runner.run(named!(streaming), input);
+    runner.run(named!(report), input);
+}
+
+fn main() {
+    run_parts(create_runner!());
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use advent_of_code::verify;
+    use advent_of_code::{verify, Error};
 
     #[test]
     fn example() {
         let input = include_str!("example.txt");
-        verify!(part1, input, "24000");
-        verify!(part2, input, "45000");
+        verify!(part1, input, 24000);
+        verify!(part2, input, 45000);
+        verify!(streaming, input, 45000);
+        verify!(
+            report,
+            input,
+            vec![
+                "elf 4: 24000 calories in 3 items (min 7000, max 9000, mean 8000.0)".to_string(),
+                "elf 3: 11000 calories in 2 items (min 5000, max 6000, mean 5500.0)".to_string(),
+                "elf 5: 10000 calories in 1 item (min 10000, max 10000, mean 10000.0)".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn ties() {
+        let input = "5\n\n3\n\n1\n4\n\n2\n\n5\n";
+        let inventory = Inventory::parse(input.lines()).unwrap();
+        let numbers = |elves: Vec<&Elf>| elves.iter().map(|elf| elf.number).collect::<Vec<_>>();
+        assert_eq!(numbers(inventory.top(2)), vec![1, 3]);
+        assert_eq!(numbers(inventory.top_with_ties(2)), vec![1, 3, 5]);
+        assert_eq!(numbers(inventory.top_with_ties(0)), vec![]);
+        assert_eq!(numbers(inventory.top_with_ties(9)), vec![1, 3, 5, 2, 4]);
+        assert_eq!(stream_top(input.lines(), 2), Ok(vec![(1, 5), (3, 5)]));
+        assert_eq!(stream_top(input.lines(), 0), Ok(vec![]));
+        assert_eq!(
+            stream_top("1\n\n\n2\nx".lines(), 1),
+            Err(Error::parse(5, "expected calories, got \"x\""))
+        );
+    }
+
+    #[test]
+    fn stats() {
+        let elf = Elf {
+            number: 1,
+            calories: vec![3, 1, 2],
+        };
+        assert_eq!(
+            elf.stats(),
+            Stats {
+                count: 3,
+                min: 1,
+                max: 3,
+                mean: 2.0
+            }
+        );
     }
 }
@@ -1,58 +1,170 @@
-use advent_of_code::{create_runner, named, Named, Runner};
+use advent_of_code::{create_runner, named, parse_lines, Answer, LineError, Named, Result, Runner};
 use std::str::Lines;
 
-#[repr(u32)]
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 enum Outcome {
-    Lose = 0,
-    Draw = 3,
-    Win = 6,
+    Lose,
+    Draw,
+    Win,
 }
 
-impl Outcome {
-    fn from_char(choice: char) -> Option<Self> {
-        match choice {
-            'X' => Some(Outcome::Lose),
-            'Y' => Some(Outcome::Draw),
-            'Z' => Some(Outcome::Win),
-            _ => None,
-        }
-    }
+const OUTCOMES: [Outcome; 3] = [Outcome::Lose, Outcome::Draw, Outcome::Win];
+
+/// A choice, as its index in `Rules::choices`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+struct Choice(usize);
+
+struct ChoiceRule {
+    name: &'static str,
+    score: u32,
+    /// What the opponent column of the strategy guide uses for it.
+    opponent: char,
+    /// What the response column uses for it, when it holds choices.
+    response: char,
 }
 
-#[repr(u32)]
-#[derive(Clone, Copy, PartialEq)]
-enum Choice {
-    Rock = 1,
-    Paper = 2,
-    Scissors = 3,
+/// A game like Rock Paper Scissors, with any number of choices.
+struct Rules {
+    choices: Vec<ChoiceRule>,
+    /// `beats[a][b]` when choice `a` beats choice `b`.
+    beats: Vec<Vec<bool>>,
+    /// Indexed like `OUTCOMES`.
+    outcome_scores: [u32; 3],
+    /// What the response column uses for each outcome, when it holds
+    /// outcomes. Indexed like `OUTCOMES`.
+    outcome_symbols: [char; 3],
 }
 
-impl Choice {
-    fn from_char(choice: char) -> Option<Self> {
-        match choice {
-            'A' | 'X' => Some(Choice::Rock),
-            'B' | 'Y' => Some(Choice::Paper),
-            'C' | 'Z' => Some(Choice::Scissors),
-            _ => None,
+impl Rules {
+    /// `choices` as `(name, score, opponent symbol, response symbol)`, and
+    /// `wins` as `(winner, loser)` names. Outcomes score and read like the
+    /// original game, which the fields allow changing.
+    fn new(choices: &[(&'static str, u32, char, char)], wins: &[(&str, &str)]) -> Self {
+        let choices = choices
+            .iter()
+            .map(|&(name, score, opponent, response)| ChoiceRule {
+                name,
+                score,
+                opponent,
+                response,
+            })
+            .collect::<Vec<_>>();
+        let mut rules = Self {
+            beats: vec![vec![false; choices.len()]; choices.len()],
+            choices,
+            outcome_scores: [0, 3, 6],
+            outcome_symbols: ['X', 'Y', 'Z'],
+        };
+        for (winner, loser) in wins {
+            let (winner, loser) = (rules.named(winner), rules.named(loser));
+            rules.beats[winner.0][loser.0] = true;
         }
+        rules
+    }
+
+    fn classic() -> Self {
+        Self::new(
+            &[
+                ("rock", 1, 'A', 'X'),
+                ("paper", 2, 'B', 'Y'),
+                ("scissors", 3, 'C', 'Z'),
+            ],
+            &[
+                ("rock", "scissors"),
+                ("paper", "rock"),
+                ("scissors", "paper"),
+            ],
+        )
+    }
+
+    fn lizard_spock() -> Self {
+        Self::new(
+            &[
+                ("rock", 1, 'A', 'V'),
+                ("paper", 2, 'B', 'W'),
+                ("scissors", 3, 'C', 'X'),
+                ("lizard", 4, 'D', 'Y'),
+                ("spock", 5, 'E', 'Z'),
+            ],
+            &[
+                ("rock", "scissors"),
+                ("rock", "lizard"),
+                ("paper", "rock"),
+                ("paper", "spock"),
+                ("scissors", "paper"),
+                ("scissors", "lizard"),
+                ("lizard", "paper"),
+                ("lizard", "spock"),
+                ("spock", "scissors"),
+                ("spock", "rock"),
+            ],
+        )
     }
 
-    fn desired(self, outcome: Outcome) -> Self {
-        match outcome {
-            Outcome::Win => match self {
-                Choice::Rock => Choice::Paper,
-                Choice::Paper => Choice::Scissors,
-                Choice::Scissors => Choice::Rock,
-            },
-            Outcome::Lose => match self {
-                Choice::Rock => Choice::Scissors,
-                Choice::Paper => Choice::Rock,
-                Choice::Scissors => Choice::Paper,
-            },
-            Outcome::Draw => self,
+    fn named(&self, name: &str) -> Choice {
+        let index = self.choices.iter().position(|choice| choice.name == name);
+        Choice(index.unwrap_or_else(|| panic!("unknown choice {name}")))
+    }
+
+    fn all(&self) -> impl Iterator<Item = Choice> {
+        (0..self.choices.len()).map(Choice)
+    }
+
+    fn opponent_symbol(&self, symbol: char) -> Option<Choice> {
+        self.all().find(|c| self.choices[c.0].opponent == symbol)
+    }
+
+    fn response_symbol(&self, symbol: char) -> Option<Choice> {
+        self.all().find(|c| self.choices[c.0].response == symbol)
+    }
+
+    fn outcome_symbol(&self, symbol: char) -> Option<Outcome> {
+        let index = self.outcome_symbols.iter().position(|&s| s == symbol)?;
+        Some(OUTCOMES[index])
+    }
+
+    /// The outcome for the player choosing `response`.
+    fn outcome(&self, opponent: Choice, response: Choice) -> Outcome {
+        if self.beats[response.0][opponent.0] {
+            Outcome::Win
+        } else if self.beats[opponent.0][response.0] {
+            Outcome::Lose
+        } else {
+            Outcome::Draw
         }
     }
+
+    fn score(&self, round: &Round) -> u32 {
+        let outcome = self.outcome(round.opponent, round.response);
+        let index = OUTCOMES.iter().position(|&o| o == outcome).unwrap();
+        self.outcome_scores[index] + self.choices[round.response.0].score
+    }
+
+    /// The best scoring response against `opponent` among those which pass
+    /// `allowed`.
+    fn best_response(&self, opponent: Choice, allowed: impl Fn(Choice) -> bool) -> Option<Choice> {
+        self.all()
+            .filter(|&response| allowed(response))
+            .max_by_key(|&response| self.score(&Round { opponent, response }))
+    }
+
+    /// The best scoring response with the given outcome, if there is one.
+    fn respond(&self, opponent: Choice, outcome: Outcome) -> Option<Choice> {
+        self.best_response(opponent, |response| {
+            self.outcome(opponent, response) == outcome
+        })
+    }
+}
+
+/// How to read the second column of the strategy guide.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Guide {
+    /// The choice to respond with.
+    Response,
+    /// The outcome the round should have.
+    Outcome,
+    /// Ignored, always responding with the best scoring choice.
+    Optimal,
 }
 
 struct Round {
@@ -61,68 +173,136 @@ struct Round {
 }
 
 impl Round {
-    fn from_part1_str(round: &str) -> Option<Self> {
-        let opponent = round.chars().next().and_then(Choice::from_char)?;
-        let response = round.chars().nth(2).and_then(Choice::from_char)?;
-        Some(Self { opponent, response })
-    }
-
-    fn from_part2_str(round: &str) -> Option<Self> {
-        let opponent = round.chars().next().and_then(Choice::from_char)?;
-        let outcome = round.chars().nth(2).and_then(Outcome::from_char)?;
-        Some(Self {
-            opponent,
-            response: opponent.desired(outcome),
-        })
+    fn parse(rules: &Rules, guide: Guide, line: &str) -> Result<Self, LineError> {
+        let [opponent, ' ', second] = line.chars().collect::<Vec<_>>()[..] else {
+            return Err(LineError::new(format!(
+                "expected an opponent and a response, got {line:?}"
+            )));
+        };
+        let opponent = rules
+            .opponent_symbol(opponent)
+            .ok_or_else(|| LineError::at(1, format!("unknown opponent choice {opponent:?}")))?;
+        let unknown = || LineError::at(3, format!("unknown response {second:?}"));
+        let response = match guide {
+            Guide::Response => rules.response_symbol(second).ok_or_else(unknown)?,
+            Guide::Outcome => {
+                let outcome = rules.outcome_symbol(second).ok_or_else(unknown)?;
+                rules
+                    .respond(opponent, outcome)
+                    .ok_or_else(|| LineError::at(3, format!("no choice gives {outcome:?}")))?
+            }
+            Guide::Optimal => rules.best_response(opponent, |_| true).unwrap(),
+        };
+        Ok(Self { opponent, response })
     }
+}
 
-    fn outcome(&self) -> Outcome {
-        if self.opponent == self.response {
-            Outcome::Draw
-        } else if self.opponent.desired(Outcome::Win) == self.response {
-            Outcome::Win
-        } else {
-            Outcome::Lose
-        }
-    }
+fn total_score(rules: &Rules, guide: Guide, input: Lines) -> Result<u32> {
+    let rounds = parse_lines(input, |line| Round::parse(rules, guide, line))?;
+    Ok(rounds.iter().map(|round| rules.score(round)).sum())
+}
 
-    fn score(&self) -> u32 {
-        self.outcome() as u32 + self.response as u32
-    }
+fn part1(input: Lines) -> Result<Answer> {
+    Ok(total_score(&Rules::classic(), Guide::Response, input)?.into())
 }
 
-fn part1(input: Lines) -> String {
-    input
-        .filter_map(Round::from_part1_str)
-        .map(|round| round.score())
-        .sum::<u32>()
-        .to_string()
+fn part2(input: Lines) -> Result<Answer> {
+    Ok(total_score(&Rules::classic(), Guide::Outcome, input)?.into())
 }
 
-fn part2(input: Lines) -> String {
-    input
-        .filter_map(Round::from_part2_str)
-        .map(|round| round.score())
-        .sum::<u32>()
-        .to_string()
+/// The most the guide's rounds could score, knowing the opponent's choices.
+fn optimal(input: Lines) -> Result<Answer> {
+    Ok(total_score(&Rules::classic(), Guide::Optimal, input)?.into())
 }
 
-fn main() {
+/// Part 2 as if the elves played Rock Paper Scissors Lizard Spock, with the
+/// best scoring response for each outcome.
+fn lizard_spock(input: Lines) -> Result<Answer> {
+    Ok(total_score(&Rules::lizard_spock(), Guide::Outcome, input)?.into())
+}
+
+pub fn run_parts(runner: &Runner) {
     let input = include_str!("input.txt");
-    let runner: &Runner = create_runner!();
+    runner.known_answers(include_str!("answers.txt"));
     runner.run(named!(part1), input);
     runner.run(named!(part2), input);
+    runner.run(named!(optimal), input);
+    runner.run(named!(lizard_spock), input);
+}
+
+fn main() {
+    run_parts(create_runner!());
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use advent_of_code::verify;
+    use advent_of_code::{verify, Error};
 
     #[test]
     fn example() {
         let input = include_str!("example.txt");
-        verify!(part1, input, "15");
-        verify!(part2, input, "12");
+        verify!(part1, input, 15);
+        verify!(part2, input, 12);
+        verify!(optimal, input, 8 + 9 + 7);
+        verify!(lizard_spock, input, (1 + 3) + 5 + (5 + 6));
+    }
+
+    #[test]
+    fn rules_decide_every_pair() {
+        for rules in [Rules::classic(), Rules::lizard_spock()] {
+            for a in rules.all() {
+                for b in rules.all() {
+                    let (ab, ba) = (rules.outcome(a, b), rules.outcome(b, a));
+                    match (a == b, ab) {
+                        (true, _) => assert_eq!(ab, Outcome::Draw),
+                        (false, Outcome::Win) => assert_eq!(ba, Outcome::Lose),
+                        (false, Outcome::Lose) => assert_eq!(ba, Outcome::Win),
+                        (false, Outcome::Draw) => panic!("{a:?} and {b:?} draw"),
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn five_way() {
+        let rules = Rules::lizard_spock();
+        // lizard against spock, lizard against rock, spock against scissors
+        let input = "E Y\nA Y\nC Z";
+        assert_eq!(
+            total_score(&rules, Guide::Response, input.lines()),
+            Ok((4 + 6) + 4 + (5 + 6))
+        );
+        // both paper and spock beat rock, spock scores more
+        let input = "A Z\nE X\nC Y";
+        assert_eq!(
+            total_score(&rules, Guide::Outcome, input.lines()),
+            Ok((5 + 6) + 3 + (3 + 3))
+        );
+        assert_eq!(
+            total_score(&rules, Guide::Optimal, "A X".lines()),
+            Ok(5 + 6)
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        let rules = Rules::classic();
+        assert_eq!(
+            total_score(&rules, Guide::Response, "A X\nD X".lines()),
+            Err(Error::parse_at(2, 1, "unknown opponent choice 'D'"))
+        );
+        assert_eq!(
+            total_score(&rules, Guide::Outcome, "A W".lines()),
+            Err(Error::parse_at(1, 3, "unknown response 'W'"))
+        );
+        assert_eq!(
+            total_score(&rules, Guide::Response, "AX".lines()),
+            Err(Error::parse(
+                1,
+                "expected an opponent and a response, got \"AX\""
+            ))
+        );
     }
 }
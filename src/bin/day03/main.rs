@@ -1,57 +1,147 @@
-use advent_of_code::{create_runner, named, Named, Runner};
-use itertools::{Chunk, Itertools};
-use std::{collections::HashSet, str::Lines};
-
-fn find_common_item_compartments(line: &str) -> Option<char> {
-    let chunks = line
-        .chars()
-        .chunks(line.len() / 2)
-        .into_iter()
-        .map(|c| c.collect::<HashSet<_>>())
-        .take(2)
-        .collect::<Vec<_>>();
-    chunks[0].intersection(&chunks[1]).next().copied()
+use advent_of_code::{
+    create_runner, named, parse_lines, Answer, Error, LineError, Named, Result, Runner,
+};
+use itertools::Itertools;
+use std::str::Lines;
+
+fn priority(c: char) -> Option<u32> {
+    match c {
+        'a'..='z' => Some(c as u32 - 'a' as u32 + 1),
+        'A'..='Z' => Some(c as u32 - 'A' as u32 + 27),
+        _ => None,
+    }
 }
 
-fn priority(c: char) -> u32 {
-    if c.is_lowercase() {
-        c as u32 - 'a' as u32 + 1
-    } else {
-        c as u32 - 'A' as u32 + 27
+fn item(priority: u32) -> char {
+    match priority {
+        1..=26 => char::from_u32('a' as u32 + priority - 1).unwrap(),
+        _ => char::from_u32('A' as u32 + priority - 27).unwrap(),
     }
 }
 
-fn part1(input: Lines) -> String {
-    input
-        .filter_map(find_common_item_compartments)
-        .map(priority)
-        .sum::<u32>()
-        .to_string()
+/// A set of items, with bit `p` set for the item of priority `p`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+struct Items(u64);
+
+impl Items {
+    const ALL: Items = Items(((1 << 52) - 1) << 1);
+
+    /// The items in `text`, which starts at column `column` of its line.
+    fn parse(text: &str, column: usize) -> Result<Self, LineError> {
+        text.chars()
+            .enumerate()
+            .try_fold(Items(0), |items, (i, c)| {
+                let p = priority(c).ok_or_else(|| {
+                    LineError::at(column + i, format!("unexpected {c:?}, items are letters"))
+                })?;
+                Ok(Items(items.0 | 1 << p))
+            })
+    }
+
+    fn intersection(self, other: Self) -> Self {
+        Items(self.0 & other.0)
+    }
+
+    fn priorities(self) -> impl Iterator<Item = u32> {
+        (1..=52).filter(move |p| self.0 & 1 << p != 0)
+    }
+
+    /// The priority of the only item, or what is wrong about `self` as the
+    /// items shared by `what`.
+    fn only(self, what: &str) -> Result<u32, String> {
+        match self.priorities().collect::<Vec<_>>()[..] {
+            [p] => Ok(p),
+            [] => Err(format!("{what} share no item")),
+            ref several => Err(format!(
+                "{what} share {} items: {}",
+                several.len(),
+                several.iter().map(|&p| item(p)).join(", ")
+            )),
+        }
+    }
 }
 
-fn find_common_item_group(group: Chunk<Lines>) -> Option<char> {
-    group
-        .into_iter()
-        .map(|line| line.chars().collect::<HashSet<_>>())
-        .reduce(|a, b| a.intersection(&b).into_iter().copied().collect())
-        .and_then(|c| c.into_iter().next())
+fn common(sets: impl IntoIterator<Item = Items>) -> Items {
+    sets.into_iter().fold(Items::ALL, Items::intersection)
 }
 
-fn part2(input: Lines) -> String {
-    input
-        .chunks(3)
+/// The items of each of `count` equal compartments.
+fn compartments(line: &str, count: usize) -> Result<Vec<Items>, LineError> {
+    let len = line.chars().count();
+    if len == 0 || !len.is_multiple_of(count) {
+        return Err(LineError::new(format!(
+            "{len} items can't be split into {count} compartments"
+        )));
+    }
+    let chars = line.chars().collect::<Vec<_>>();
+    chars
+        .chunks(len / count)
+        .enumerate()
+        .map(|(i, chunk)| Items::parse(&String::from_iter(chunk), 1 + i * len / count))
+        .collect()
+}
+
+/// The priority of the one item in every compartment of each rucksack.
+fn shared_in_compartments(input: Lines, count: usize) -> Result<Vec<u32>> {
+    parse_lines(input, |line| {
+        let shared = common(compartments(line, count)?);
+        Ok(shared.only("the compartments")?)
+    })
+}
+
+/// The priority of the badge of each group of `size` rucksacks, the one
+/// item all of them carry. Errors are reported on a group's first line.
+fn badges(input: Lines, size: usize) -> Result<Vec<u32>> {
+    let rucksacks = parse_lines(input, |line| Items::parse(line, 1))?;
+    rucksacks
+        .chunks(size)
+        .enumerate()
+        .map(|(i, group)| {
+            let first_line = i * size + 1;
+            if group.len() < size {
+                return Err(Error::parse(
+                    first_line,
+                    format!("the last group has {} of {size} rucksacks", group.len()),
+                ));
+            }
+            let what = format!(
+                "the rucksacks on lines {first_line}..={}",
+                first_line + size - 1
+            );
+            common(group.iter().copied())
+                .only(&what)
+                .map_err(|message| Error::parse(first_line, message))
+        })
+        .collect()
+}
+
+fn part1(input: Lines) -> Result<Answer> {
+    Ok(shared_in_compartments(input, 2)?.iter().sum::<u32>().into())
+}
+
+fn part2(input: Lines) -> Result<Answer> {
+    Ok(badges(input, 3)?.iter().sum::<u32>().into())
+}
+
+/// The badge of every group, in order.
+fn group_badges(input: Lines) -> Result<Answer> {
+    Ok(badges(input, 3)?
         .into_iter()
-        .filter_map(find_common_item_group)
-        .map(priority)
-        .sum::<u32>()
-        .to_string()
+        .map(item)
+        .collect::<String>()
+        .into())
 }
 
-fn main() {
+pub fn run_parts(runner: &Runner) {
     let input = include_str!("input.txt");
-    let runner: &Runner = create_runner!();
+    runner.known_answers(include_str!("answers.txt"));
     runner.run(named!(part1), input);
     runner.run(named!(part2), input);
+    runner.run(named!(group_badges), input);
+}
+
+fn main() {
+    run_parts(create_runner!());
 }
 
 #[cfg(test)]
@@ -62,7 +152,67 @@ mod tests {
     #[test]
     fn example() {
         let input = include_str!("example.txt");
-        verify!(part1, input, "157");
-        verify!(part2, input, "70");
+        verify!(part1, input, 157);
+        verify!(part2, input, 70);
+        verify!(group_badges, input, "rZ");
+    }
+
+    #[test]
+    fn items() {
+        let items = Items::parse("aAzZa", 1).unwrap();
+        assert_eq!(items.priorities().collect::<Vec<_>>(), vec![1, 26, 27, 52]);
+        assert_eq!(items.priorities().map(item).collect::<String>(), "azAZ");
+        assert_eq!(
+            common([items, Items::parse("bZ", 1).unwrap()]),
+            Items(1 << 52)
+        );
+        assert_eq!(
+            Items::parse("ab1", 4),
+            Err(LineError::at(6, "unexpected '1', items are letters"))
+        );
+    }
+
+    #[test]
+    fn any_size() {
+        assert_eq!(shared_in_compartments("abcaxdaye".lines(), 3), Ok(vec![1]));
+        assert_eq!(
+            badges("ab\nbc\nbd\nbe\nxyb\nzb".lines(), 2),
+            Ok(vec![2, 2, 2])
+        );
+        assert_eq!(badges("ab\nbc\nbd\nbe".lines(), 4), Ok(vec![2]));
+    }
+
+    #[test]
+    fn diagnoses() {
+        assert_eq!(
+            shared_in_compartments("abcd\nabab".lines(), 2),
+            Err(Error::parse(1, "the compartments share no item"))
+        );
+        assert_eq!(
+            shared_in_compartments("abcd\nabab".lines(), 3),
+            Err(Error::parse(
+                1,
+                "4 items can't be split into 3 compartments"
+            ))
+        );
+        assert_eq!(
+            shared_in_compartments("aBcaBc".lines(), 2),
+            Err(Error::parse(1, "the compartments share 3 items: a, c, B"))
+        );
+        assert_eq!(
+            shared_in_compartments("ab-b".lines(), 2),
+            Err(Error::parse_at(1, 3, "unexpected '-', items are letters"))
+        );
+        assert_eq!(
+            badges("ab\nbc\nbd\nxa\nxb".lines(), 3),
+            Err(Error::parse(4, "the last group has 2 of 3 rucksacks"))
+        );
+        assert_eq!(
+            badges("ab\nbc\nbd\nxya\nxyb\nxyc".lines(), 3),
+            Err(Error::parse(
+                4,
+                "the rucksacks on lines 4..=6 share 2 items: x, y"
+            ))
+        );
     }
 }
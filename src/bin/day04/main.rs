@@ -1,59 +1,67 @@
-use advent_of_code::{create_runner, named, Named, Runner};
-use std::{cmp::Ordering, ops::Range, str::Lines};
+use advent_of_code::{
+    captures, create_runner, named, parse_lines, Answer, IntervalSet, LineError, Named, Result,
+    Runner,
+};
+use lazy_regex::regex;
+use std::{ops::Range, str::Lines};
 
-// consider using Interval from https://docs.rs/intervallum/1.4.0/interval/interval/index.html
-
-fn parse_range(range: &str) -> Option<Range<u32>> {
-    range
-        .split_once('-')
-        .and_then(|(a, b)| a.parse::<u32>().ok().zip(b.parse::<u32>().ok()))
-        .map(|(a, b)| a..b + 1)
+/// The sections `first` to `last`, both included. The range ends past
+/// `last`, so it is widened to hold `u32::MAX + 1`.
+fn assignment(first: u32, last: u32) -> Result<Range<u64>, LineError> {
+    if last < first {
+        return Err(LineError::new(format!(
+            "sections {first}-{last} end before they start"
+        )));
+    }
+    Ok(u64::from(first)..u64::from(last) + 1)
 }
 
-fn parse_ranges(line: &str) -> Option<(Range<u32>, Range<u32>)> {
-    line.split_once(',')
-        .and_then(|(a, b)| parse_range(a).zip(parse_range(b)))
+fn parse_ranges(line: &str) -> Result<(Range<u64>, Range<u64>), LineError> {
+    let (a, b, c, d) =
+        captures::<(u32, u32, u32, u32)>(regex!(r"^(\d+)-(\d+),(\d+)-(\d+)$"), line)?;
+    Ok((assignment(a, b)?, assignment(c, d)?))
 }
 
-fn either_contains(pair: &(Range<u32>, Range<u32>)) -> bool {
+fn either_contains(pair: &(Range<u64>, Range<u64>)) -> bool {
     let (a, b) = pair;
-    let start_cmp = a.start.cmp(&b.start);
-    let end_cmp = a.end.cmp(&b.end);
-    start_cmp != end_cmp || start_cmp == Ordering::Equal
+    let (a, b) = (IntervalSet::from(a.clone()), IntervalSet::from(b.clone()));
+    a.is_superset(&b) || b.is_superset(&a)
 }
 
-fn overlaps(pair: &(Range<u32>, Range<u32>)) -> bool {
+fn overlaps(pair: &(Range<u64>, Range<u64>)) -> bool {
     let (a, b) = pair;
-    a.end > b.start && b.end > a.start
+    IntervalSet::from(a.clone()).overlaps(&IntervalSet::from(b.clone()))
 }
 
-fn part1(input: Lines) -> String {
-    input
-        .filter_map(parse_ranges)
-        .filter(either_contains)
+fn part1(input: Lines) -> Result<Answer> {
+    let pairs = parse_lines(input, parse_ranges)?;
+    Ok(pairs
+        .iter()
+        .filter(|pair| either_contains(pair))
         .count()
-        .to_string()
+        .into())
 }
 
-fn part2(input: Lines) -> String {
-    input
-        .filter_map(parse_ranges)
-        .filter(overlaps)
-        .count()
-        .to_string()
+fn part2(input: Lines) -> Result<Answer> {
+    let pairs = parse_lines(input, parse_ranges)?;
+    Ok(pairs.iter().filter(|pair| overlaps(pair)).count().into())
 }
 
-fn main() {
+pub fn run_parts(runner: &Runner) {
     let input = include_str!("input.txt");
-    let runner: &Runner = create_runner!();
+    runner.known_answers(include_str!("answers.txt"));
     runner.run(named!(part1), input);
     runner.run(named!(part2), input);
 }
 
+fn main() {
+    run_parts(create_runner!());
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use advent_of_code::verify;
+    use advent_of_code::{verify, Error};
 
     #[test]
     fn test_contains() {
@@ -84,7 +92,26 @@ mod tests {
     #[test]
     fn example() {
         let input = include_str!("example.txt");
-        verify!(part1, input, "2");
-        verify!(part2, input, "4");
+        verify!(part1, input, 2);
+        verify!(part2, input, 4);
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            part1("2-4,6-8\n5-3,1-2".lines()),
+            Err(Error::parse(2, "sections 5-3 end before they start"))
+        );
+        assert!(matches!(
+            part2("2-4,6-8\n2-4;6-8".lines()),
+            Err(Error::Parse { line: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn last_section() {
+        let input = "4294967295-4294967295,4294967290-4294967295";
+        verify!(part1, input, 1);
+        verify!(part2, input, 1);
     }
 }
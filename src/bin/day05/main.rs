@@ -1,6 +1,10 @@
-use advent_of_code::{create_runner, named, Named, Runner};
-use lazy_regex::regex_captures;
-use std::str::Lines;
+use advent_of_code::{
+    captures, create_runner, named, sections, trace, Answer, Error, LineError, Named, Render,
+    Result, Runner,
+};
+use itertools::Itertools;
+use lazy_regex::regex;
+use std::{fmt::Display, str::Lines};
 
 struct Action {
     count: usize,
@@ -9,16 +13,91 @@ struct Action {
 }
 
 impl Action {
-    fn parse(line: &str) -> Option<Self> {
-        let (_, count, from, to) = regex_captures!(r"move (\d+) from (\d+) to (\d+)", line)?;
-        Some(Self {
-            count: count.parse::<usize>().ok()?,
-            from: from.parse::<usize>().ok()? - 1,
-            to: to.parse::<usize>().ok()? - 1,
+    fn parse(line: &str) -> Result<Self, LineError> {
+        let (count, from, to) =
+            captures::<(usize, usize, usize)>(regex!(r"^move (\d+) from (\d+) to (\d+)$"), line)?;
+        let stack = |number: usize| {
+            number
+                .checked_sub(1)
+                .ok_or_else(|| LineError::new("stacks are numbered from 1"))
+        };
+        Ok(Self {
+            count,
+            from: stack(from)?,
+            to: stack(to)?,
         })
     }
 }
 
+impl Display for Action {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "move {} from {} to {}",
+            self.count,
+            self.from + 1,
+            self.to + 1
+        )
+    }
+}
+
+/// How a crane carries out an action, as the number of crates it lifts on
+/// each trip. Crates lifted together keep their order.
+trait Crane {
+    fn trips(&self, count: usize) -> Vec<usize>;
+}
+
+/// Lifts one crate at a time, which reverses the crates it moves.
+struct CrateMover9000;
+
+impl Crane for CrateMover9000 {
+    fn trips(&self, count: usize) -> Vec<usize> {
+        vec![1; count]
+    }
+}
+
+/// Lifts all the crates of an action at once.
+struct CrateMover9001;
+
+impl Crane for CrateMover9001 {
+    fn trips(&self, count: usize) -> Vec<usize> {
+        vec![count]
+    }
+}
+
+/// Lifts up to `capacity` crates at once.
+struct Bounded {
+    capacity: usize,
+}
+
+impl Bounded {
+    fn new(capacity: usize) -> Result<Self> {
+        if capacity == 0 {
+            return Err(Error::InvalidInput(
+                "a crane must lift at least one crate".to_string(),
+            ));
+        }
+        Ok(Self { capacity })
+    }
+}
+
+impl Crane for Bounded {
+    fn trips(&self, count: usize) -> Vec<usize> {
+        (0..count)
+            .step_by(self.capacity)
+            .map(|lifted| self.capacity.min(count - lifted))
+            .collect()
+    }
+}
+
+/// An action as a crane carried it out, which is enough to undo it.
+struct Move {
+    from: usize,
+    to: usize,
+    trips: Vec<usize>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
 struct Stacks {
     supplies: Vec<Vec<char>>,
 }
@@ -40,7 +119,7 @@ impl Stacks {
             self.supplies
                 .iter_mut()
                 .zip(crates.by_ref())
-                .for_each(|(stack, c)| stack.extend(c.into_iter()));
+                .for_each(|(stack, c)| stack.extend(c));
             self.supplies
                 .extend(crates.map(|c| c.into_iter().collect::<Vec<_>>()));
         }
@@ -54,16 +133,51 @@ impl Stacks {
             .collect()
     }
 
-    fn do_single(&mut self, action: &Action) {
-        let from_stack = &mut self.supplies[action.from];
-        let removed = from_stack.split_off(from_stack.len() - action.count);
-        self.supplies[action.to].extend(removed.into_iter().rev());
+    /// Moves the top `count` crates of `from` onto `to`, keeping their order.
+    fn lift(&mut self, from: usize, to: usize, count: usize) {
+        let from_stack = &mut self.supplies[from];
+        let lifted = from_stack.split_off(from_stack.len() - count);
+        self.supplies[to].extend(lifted);
+    }
+
+    /// Carries out `action` with `crane`, unless a stack doesn't exist or
+    /// has too few crates.
+    fn apply(&mut self, crane: &dyn Crane, action: &Action) -> Result<Move, LineError> {
+        for stack in [action.from, action.to] {
+            if stack >= self.supplies.len() {
+                return Err(LineError::new(format!(
+                    "there is no stack {}, only {}",
+                    stack + 1,
+                    self.supplies.len()
+                )));
+            }
+        }
+        let available = self.supplies[action.from].len();
+        if action.count > available {
+            return Err(LineError::new(format!(
+                "stack {} has only {available} crates",
+                action.from + 1
+            )));
+        }
+        let done = Move {
+            from: action.from,
+            to: action.to,
+            trips: crane.trips(action.count),
+        };
+        self.redo(&done);
+        Ok(done)
+    }
+
+    fn redo(&mut self, done: &Move) {
+        for &count in &done.trips {
+            self.lift(done.from, done.to, count);
+        }
     }
 
-    fn do_batch(&mut self, action: &Action) {
-        let from_stack = &mut self.supplies[action.from];
-        let removed = from_stack.split_off(from_stack.len() - action.count);
-        self.supplies[action.to].extend(removed);
+    fn undo(&mut self, done: &Move) {
+        for &count in done.trips.iter().rev() {
+            self.lift(done.to, done.from, count);
+        }
     }
 
     fn top_all(&self) -> String {
@@ -71,39 +185,148 @@ impl Stacks {
     }
 }
 
-fn part1(input: Lines) -> String {
-    let mut stacks = Stacks::new();
-    let mut iter = input;
+/// The stacks drawn like the puzzle input, stack numbers included.
+impl Render for Stacks {
+    fn render(&self) -> Vec<String> {
+        let height = self.supplies.iter().map(Vec::len).max().unwrap_or(0);
+        let mut rows = (0..height)
+            .rev()
+            .map(|level| {
+                self.supplies
+                    .iter()
+                    .map(|stack| match stack.get(level) {
+                        Some(c) => format!("[{c}]"),
+                        None => "   ".to_string(),
+                    })
+                    .join(" ")
+            })
+            .collect::<Vec<_>>();
+        rows.push(
+            (1..=self.supplies.len())
+                .map(|number| format!(" {number} "))
+                .join(" "),
+        );
+        rows
+    }
+}
 
-    iter.by_ref()
-        .take_while(|line| !line.is_empty())
-        .for_each(|line| stacks.read_line(line));
-    stacks.flip_all();
+/// The stacks along with the moves made so far, which can be undone and
+/// replayed.
+struct Simulation {
+    stacks: Stacks,
+    done: Vec<Move>,
+    undone: Vec<Move>,
+}
 
-    iter.filter_map(Action::parse)
-        .for_each(|action| stacks.do_single(&action));
-    stacks.top_all()
+impl Simulation {
+    fn new(stacks: Stacks) -> Self {
+        Self {
+            stacks,
+            done: Vec::new(),
+            undone: Vec::new(),
+        }
+    }
+
+    /// Carries out `action`, which forgets the moves undone so far.
+    fn perform(&mut self, crane: &dyn Crane, action: &Action) -> Result<(), LineError> {
+        let done = self.stacks.apply(crane, action)?;
+        self.done.push(done);
+        self.undone.clear();
+        Ok(())
+    }
+
+    /// Undoes the last move, if any.
+    fn undo(&mut self) -> bool {
+        let Some(done) = self.done.pop() else {
+            return false;
+        };
+        self.stacks.undo(&done);
+        self.undone.push(done);
+        true
+    }
+
+    /// Replays the last move undone, if any.
+    fn redo(&mut self) -> bool {
+        let Some(done) = self.undone.pop() else {
+            return false;
+        };
+        self.stacks.redo(&done);
+        self.done.push(done);
+        true
+    }
 }
 
-fn part2(input: Lines) -> String {
+/// The starting stacks and each action along with its line number.
+fn parse_input(input: Lines) -> Result<(Stacks, Vec<(usize, Action)>)> {
+    let sections = sections(input);
+    let [drawing, actions] = &sections[..] else {
+        return Err(Error::InvalidInput(format!(
+            "expected the stacks and the actions, got {} sections",
+            sections.len()
+        )));
+    };
     let mut stacks = Stacks::new();
-    let mut iter = input;
-
-    iter.by_ref()
-        .take_while(|line| !line.is_empty())
-        .for_each(|line| stacks.read_line(line));
+    drawing.lines.iter().for_each(|line| stacks.read_line(line));
     stacks.flip_all();
+    let numbered = (actions.first_line..).zip(actions.parse_lines(Action::parse)?);
+    Ok((stacks, numbered.collect()))
+}
 
-    iter.filter_map(Action::parse)
-        .for_each(|action| stacks.do_batch(&action));
-    stacks.top_all()
+/// Carries out every action with `crane`. An action which can't be carried
+/// out is reported as an error on its line.
+fn rearrange(input: Lines, crane: &dyn Crane) -> Result<Simulation> {
+    let (stacks, actions) = parse_input(input)?;
+    let mut simulation = Simulation::new(stacks);
+    trace("start", &simulation.stacks);
+    for (line, action) in &actions {
+        simulation
+            .perform(crane, action)
+            .map_err(|error| error.on_line(*line))?;
+        trace(action, &simulation.stacks);
+    }
+    Ok(simulation)
 }
 
-fn main() {
+fn part1(input: Lines) -> Result<Answer> {
+    Ok(rearrange(input, &CrateMover9000)?.stacks.top_all().into())
+}
+
+fn part2(input: Lines) -> Result<Answer> {
+    Ok(rearrange(input, &CrateMover9001)?.stacks.top_all().into())
+}
+
+/// Part 2 with a crane which lifts at most three crates at once.
+fn bounded(input: Lines) -> Result<Answer> {
+    let crane = Bounded::new(3)?;
+    Ok(rearrange(input, &crane)?.stacks.top_all().into())
+}
+
+/// Checks the move log of part 2: undoing every move must restore the
+/// drawing, and replaying them must give part 2's answer again.
+fn replay(input: Lines) -> Result<Answer> {
+    let (start, _) = parse_input(input.clone())?;
+    let mut simulation = rearrange(input, &CrateMover9001)?;
+    while simulation.undo() {}
+    if simulation.stacks != start {
+        return Err(Error::NoSolution(
+            "undoing every move did not restore the drawing".to_string(),
+        ));
+    }
+    while simulation.redo() {}
+    Ok(simulation.stacks.top_all().into())
+}
+
+pub fn run_parts(runner: &Runner) {
     let input = include_str!("input.txt");
-    let runner: &Runner = create_runner!();
+    runner.known_answers(include_str!("answers.txt"));
     runner.run(named!(part1), input);
     runner.run(named!(part2), input);
+    runner.run(named!(bounded), input);
+    runner.run(named!(replay), input);
+}
+
+fn main() {
+    run_parts(create_runner!());
 }
 
 #[cfg(test)]
@@ -116,5 +339,76 @@ mod tests {
         let input = include_str!("example.txt");
         verify!(part1, input, "CMZ");
         verify!(part2, input, "MCD");
+        verify!(bounded, input, "MCD");
+        verify!(replay, input, "MCD");
+    }
+
+    #[test]
+    fn cranes() {
+        assert_eq!(CrateMover9000.trips(3), vec![1, 1, 1]);
+        assert_eq!(CrateMover9001.trips(3), vec![3]);
+        let crane = Bounded::new(2).unwrap();
+        assert_eq!(crane.trips(5), vec![2, 2, 1]);
+        assert_eq!(crane.trips(0), vec![]);
+        assert_eq!(
+            Bounded::new(0).err(),
+            Some(Error::InvalidInput(
+                "a crane must lift at least one crate".to_string()
+            ))
+        );
+        let mut stacks = Stacks {
+            supplies: vec![vec!['D', 'C', 'B', 'A'], vec![]],
+        };
+        let done = stacks
+            .apply(
+                &Bounded::new(3).unwrap(),
+                &Action::parse("move 4 from 1 to 2").unwrap(),
+            )
+            .unwrap();
+        assert_eq!(stacks.supplies[1], vec!['C', 'B', 'A', 'D']);
+        stacks.undo(&done);
+        assert_eq!(stacks.supplies, vec![vec!['D', 'C', 'B', 'A'], vec![]]);
+    }
+
+    #[test]
+    fn undo() {
+        let input = include_str!("example.txt");
+        let (start, _) = parse_input(input.lines()).unwrap();
+        let mut simulation = rearrange(input.lines(), &CrateMover9000).unwrap();
+        let end = simulation.stacks.clone();
+        assert!(simulation.undo() && simulation.undo());
+        assert!(simulation.redo());
+        assert_eq!(simulation.stacks.top_all(), "MZ");
+        simulation
+            .perform(
+                &CrateMover9000,
+                &Action::parse("move 1 from 1 to 2").unwrap(),
+            )
+            .unwrap();
+        assert!(!simulation.redo());
+        while simulation.undo() {}
+        assert_eq!(simulation.stacks, start);
+        assert_ne!(simulation.stacks, end);
+    }
+
+    #[test]
+    fn invalid_moves() {
+        let input = include_str!("example.txt").replace("move 3 from 1", "move 4 from 1");
+        assert_eq!(
+            part1(input.lines()),
+            Err(Error::parse(7, "stack 1 has only 3 crates"))
+        );
+        let input = include_str!("example.txt").replace("from 2 to 1", "from 4 to 1");
+        assert_eq!(
+            part2(input.lines()),
+            Err(Error::parse(6, "there is no stack 4, only 3"))
+        );
+    }
+
+    #[test]
+    fn render() {
+        let input = include_str!("example.txt");
+        let (stacks, _) = parse_input(input.lines()).unwrap();
+        assert_eq!(stacks.render(), input.lines().take(4).collect::<Vec<_>>());
     }
 }
@@ -1,4 +1,4 @@
-use advent_of_code::{create_runner, named, Named, Runner};
+use advent_of_code::{create_runner, named, Answer, Error, Named, Result, Runner};
 use itertools::Itertools;
 use std::{
     collections::{hash_map::Entry, HashMap, VecDeque},
@@ -50,23 +50,32 @@ fn check_distinct(line: &str, count: usize) -> Option<usize> {
         .map(|(i, _)| i + count)
 }
 
-fn part1(input: Lines) -> String {
-    let buffer = input.into_iter().next().unwrap_or_default();
-    check_distinct(buffer, 4).unwrap().to_string()
+/// How many characters are read before the first `count` distinct ones.
+fn marker(mut input: Lines, count: usize) -> Result<usize> {
+    let buffer = input.next().unwrap_or_default();
+    check_distinct(buffer, count)
+        .ok_or_else(|| Error::NoSolution(format!("no {count} characters in a row are distinct")))
 }
 
-fn part2(input: Lines) -> String {
-    let buffer = input.into_iter().next().unwrap_or_default();
-    check_distinct(buffer, 14).unwrap().to_string()
+fn part1(input: Lines) -> Result<Answer> {
+    Ok(marker(input, 4)?.into())
 }
 
-fn main() {
+fn part2(input: Lines) -> Result<Answer> {
+    Ok(marker(input, 14)?.into())
+}
+
+pub fn run_parts(runner: &Runner) {
     let input = include_str!("input.txt");
-    let runner: &Runner = create_runner!();
+    runner.known_answers(include_str!("answers.txt"));
     runner.run(named!(part1), input);
     runner.run(named!(part2), input);
 }
 
+fn main() {
+    run_parts(create_runner!());
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -75,7 +84,18 @@ mod tests {
     #[test]
     fn example() {
         let input = include_str!("example.txt");
-        verify!(part1, input, "7");
-        verify!(part2, input, "19");
+        verify!(part1, input, 7);
+        verify!(part2, input, 19);
+    }
+
+    #[test]
+    fn no_marker() {
+        assert_eq!(
+            part1("abcabc".lines()),
+            Err(Error::NoSolution(
+                "no 4 characters in a row are distinct".to_string()
+            ))
+        );
+        assert!(part2("".lines()).is_err());
     }
 }
@@ -1,4 +1,4 @@
-use advent_of_code::{create_runner, named, Named, Runner};
+use advent_of_code::{create_runner, named, Answer, Named, Result, Runner};
 use std::{cmp::min, str::Lines};
 
 #[derive(Debug)]
@@ -58,8 +58,8 @@ fn find_total_size(mut input: Lines, threshold: u64) -> u64 {
     find_total_size_helper(&mut input, threshold).total_size
 }
 
-fn part1(input: Lines) -> String {
-    find_total_size(input, 100000).to_string()
+fn part1(input: Lines) -> Result<Answer> {
+    Ok(find_total_size(input, 100000).into())
 }
 
 #[derive(Debug)]
@@ -119,20 +119,24 @@ fn find_smallest_size(mut input: Lines, threshold: u64) -> u64 {
     find_smallest_size_helper(&mut input, threshold).smallest_size
 }
 
-fn part2(input: Lines) -> String {
+fn part2(input: Lines) -> Result<Answer> {
     let total_size = find_smallest_size_helper(&mut input.clone(), 0).dir_size;
     let unused_space = 70000000 - total_size;
     let threshold = 30000000 - unused_space;
-    find_smallest_size(input, threshold).to_string()
+    Ok(find_smallest_size(input, threshold).into())
 }
 
-fn main() {
+pub fn run_parts(runner: &Runner) {
     let input = include_str!("input.txt");
-    let runner: &Runner = create_runner!();
+    runner.known_answers(include_str!("answers.txt"));
     runner.run(named!(part1), input);
     runner.run(named!(part2), input);
 }
 
+fn main() {
+    run_parts(create_runner!());
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -141,7 +145,7 @@ mod tests {
     #[test]
     fn example() {
         let input = include_str!("example.txt");
-        verify!(part1, input, "95437");
-        verify!(part2, input, "24933642");
+        verify!(part1, input, 95437);
+        verify!(part2, input, 24933642);
     }
 }
@@ -1,48 +1,40 @@
-use advent_of_code::{create_runner, named, Named, Runner};
+use advent_of_code::{create_runner, named, Answer, Grid, Named, Pos, Result, Runner};
 use std::str::Lines;
 
 struct Forest {
-    rows: usize,
-    cols: usize,
-    trees: Vec<Vec<i8>>,
+    trees: Grid<i8>,
 }
 
 impl Forest {
-    fn parse(input: Lines) -> Self {
-        let trees = input
-            .into_iter()
-            .map(|line| {
-                line.chars()
-                    .into_iter()
-                    .map(|c| c.to_digit(10).unwrap() as i8)
-                    .collect::<Vec<_>>()
-            })
-            .collect::<Vec<_>>();
-        Self {
-            rows: trees.len(),
-            cols: trees[0].len(),
-            trees,
-        }
+    fn parse(input: Lines) -> Result<Self> {
+        let trees = Grid::parse(input, |c| c.to_digit(10).map(|height| height as i8))?;
+        Ok(Self { trees })
+    }
+
+    /// Trees from `pos` to each edge, nearest first.
+    fn lines_of_sight(&self, Pos { x, y }: Pos<usize>) -> [Vec<i8>; 4] {
+        let row = self.trees.row(y);
+        let column = self.trees.column(x).copied().collect::<Vec<_>>();
+        [
+            column[..y].iter().rev().copied().collect(),
+            column[y + 1..].to_vec(),
+            row[..x].iter().rev().copied().collect(),
+            row[x + 1..].to_vec(),
+        ]
     }
 
-    fn is_visible(&self, ro: usize, co: usize) -> bool {
-        let height = self.trees[ro][co];
-        (0..ro).all(|r| self.trees[r][co] < height)
-            || (ro + 1..self.rows).all(|r| self.trees[r][co] < height)
-            || (0..co).all(|c| self.trees[ro][c] < height)
-            || (co + 1..self.cols).all(|c| self.trees[ro][c] < height)
+    fn is_visible(&self, pos: Pos<usize>) -> bool {
+        let height = self.trees[pos];
+        self.lines_of_sight(pos)
+            .iter()
+            .any(|trees| trees.iter().all(|&tree| tree < height))
     }
 
     fn count_visible(&self) -> usize {
-        let mut count = 0;
-        for r in 0..self.rows {
-            for c in 0..self.cols {
-                if self.is_visible(r, c) {
-                    count += 1
-                }
-            }
-        }
-        count
+        self.trees
+            .positions()
+            .filter(|&pos| self.is_visible(pos))
+            .count()
     }
 
     fn scan<I>(it: I) -> usize
@@ -59,44 +51,42 @@ impl Forest {
         count
     }
 
-    fn scenic_score(&self, ro: usize, co: usize) -> usize {
-        let height = self.trees[ro][co];
-        [
-            Self::scan((0..ro).rev().map(|r| self.trees[r][co] >= height)),
-            Self::scan((ro + 1..self.rows).map(|r| self.trees[r][co] >= height)),
-            Self::scan((0..co).rev().map(|c| self.trees[ro][c] >= height)),
-            Self::scan((co + 1..self.cols).map(|c| self.trees[ro][c] >= height)),
-        ]
-        .into_iter()
-        .product()
+    fn scenic_score(&self, pos: Pos<usize>) -> usize {
+        let height = self.trees[pos];
+        self.lines_of_sight(pos)
+            .iter()
+            .map(|trees| Self::scan(trees.iter().map(|&tree| tree >= height)))
+            .product()
     }
 
     fn best_scenic_score(&self) -> usize {
-        let mut best_score = 0;
-        for r in 0..self.rows {
-            for c in 0..self.cols {
-                best_score = best_score.max(self.scenic_score(r, c))
-            }
-        }
-        best_score
+        self.trees
+            .positions()
+            .map(|pos| self.scenic_score(pos))
+            .max()
+            .unwrap_or_default()
     }
 }
 
-fn part1(input: Lines) -> String {
-    Forest::parse(input).count_visible().to_string()
+fn part1(input: Lines) -> Result<Answer> {
+    Ok(Forest::parse(input)?.count_visible().into())
 }
 
-fn part2(input: Lines) -> String {
-    Forest::parse(input).best_scenic_score().to_string()
+fn part2(input: Lines) -> Result<Answer> {
+    Ok(Forest::parse(input)?.best_scenic_score().into())
 }
 
-fn main() {
+pub fn run_parts(runner: &Runner) {
     let input = include_str!("input.txt");
-    let runner: &Runner = create_runner!();
+    runner.known_answers(include_str!("answers.txt"));
     runner.run(named!(part1), input);
     runner.run(named!(part2), input);
 }
 
+fn main() {
+    run_parts(create_runner!());
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -105,7 +95,7 @@ mod tests {
     #[test]
     fn example() {
         let input = include_str!("example.txt");
-        verify!(part1, input, "21");
-        verify!(part2, input, "8");
+        verify!(part1, input, 21);
+        verify!(part2, input, 8);
     }
 }
@@ -1,83 +1,34 @@
-use advent_of_code::{create_runner, named, Named, Runner};
+use advent_of_code::{
+    captures, create_runner, named, parse_lines, trace, Answer, LineError, Named, Pos, Render,
+    Result, Runner,
+};
+use itertools::Itertools;
+use lazy_regex::regex;
 use std::{collections::HashSet, str::Lines};
 
-#[derive(PartialEq, Eq, Hash, Clone, Copy)]
-struct Position {
-    x: isize,
-    y: isize,
-}
-
-impl Position {
-    fn new(x: isize, y: isize) -> Self {
-        Self { x, y }
-    }
-}
-
-impl std::fmt::Display for Position {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "({}, {})", self.x, self.y)
-    }
-}
-
-impl std::ops::Sub<Position> for Position {
-    type Output = Offset;
-
-    fn sub(self, rhs: Position) -> Self::Output {
-        Self::Output::new(self.x - rhs.x, self.y - rhs.y)
-    }
-}
-
-impl std::ops::Add<Offset> for Position {
-    type Output = Position;
-
-    fn add(self, rhs: Offset) -> Self::Output {
-        Self::Output::new(self.x + rhs.x, self.y + rhs.y)
-    }
-}
-
-#[derive(Clone, Copy)]
-struct Offset {
-    x: isize,
-    y: isize,
-}
-
-impl std::fmt::Display for Offset {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "({}, {})", self.x, self.y)
-    }
-}
-
-impl Offset {
-    fn new(x: isize, y: isize) -> Self {
-        Self { x, y }
-    }
-}
-
 struct State {
-    knots: Vec<Position>,
+    knots: Vec<Pos>,
 }
 
 impl State {
     fn new(num_knots: usize) -> Self {
-        let knots = std::iter::repeat(Position::new(0, 0))
-            .take(num_knots)
-            .collect::<Vec<_>>();
+        let knots = std::iter::repeat_n(Pos::default(), num_knots).collect::<Vec<_>>();
         Self { knots }
     }
 
-    fn tail(&self) -> &Position {
+    fn tail(&self) -> &Pos {
         self.knots.last().unwrap()
     }
 
-    fn tail_move(tail_diff: Offset) -> Offset {
-        if tail_diff.x.abs() >= 2 || tail_diff.y.abs() >= 2 {
-            Offset::new(tail_diff.x.signum(), tail_diff.y.signum())
+    fn tail_move(tail_diff: Pos) -> Pos {
+        if tail_diff.chebyshev(Pos::default()) >= 2 {
+            tail_diff.map(isize::signum)
         } else {
-            Offset::new(0, 0)
+            Pos::default()
         }
     }
 
-    fn next(&self, offset: &Offset) -> Self {
+    fn next(&self, offset: &Pos) -> Self {
         let new_head = self.knots[0] + *offset;
         let knots = Some(new_head)
             .into_iter()
@@ -99,61 +50,120 @@ impl State {
     }
 }
 
+/// Knots are drawn as in the puzzle: `H`, then `T` for the only other knot
+/// or numbers for several, over `s` at the start and `.` elsewhere. `y`
+/// counts up, so the top row is the highest.
+impl Render for State {
+    fn render(&self) -> Vec<String> {
+        let start = Pos::default();
+        let bounds = |coordinate: fn(&Pos) -> isize| {
+            self.knots
+                .iter()
+                .chain([&start])
+                .map(coordinate)
+                .minmax()
+                .into_option()
+                .unwrap()
+        };
+        let (min_x, max_x) = bounds(|p| p.x);
+        let (min_y, max_y) = bounds(|p| p.y);
+        (min_y..=max_y)
+            .rev()
+            .map(|y| {
+                (min_x..=max_x)
+                    .map(|x| {
+                        let pos = Pos::new(x, y);
+                        match self.knots.iter().position(|&knot| knot == pos) {
+                            Some(0) => 'H',
+                            Some(1) if self.knots.len() == 2 => 'T',
+                            Some(i) => char::from_digit(i as u32, 36).unwrap_or('#'),
+                            None if pos == start => 's',
+                            None => '.',
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
 struct Command {
-    offset: Offset,
+    offset: Pos,
     count: usize,
 }
 
 impl Command {
-    fn parse(line: &str) -> Option<Self> {
-        let (direction, count) = line.split_once(' ')?;
-        let count = count.parse::<usize>().ok()?;
+    fn parse(line: &str) -> Result<Self, LineError> {
+        let (direction, count) = captures::<(char, usize)>(regex!(r"^([RLUD]) (\d+)$"), line)?;
         let offset = match direction {
-            "R" => Some(Offset::new(1, 0)),
-            "L" => Some(Offset::new(-1, 0)),
-            "U" => Some(Offset::new(0, 1)),
-            "D" => Some(Offset::new(0, -1)),
-            _ => None,
-        }?;
-        Some(Self { offset, count })
+            'R' => Pos::new(1, 0),
+            'L' => Pos::new(-1, 0),
+            'U' => Pos::new(0, 1),
+            _ => Pos::new(0, -1),
+        };
+        Ok(Self { offset, count })
     }
 }
 
-fn run(mut state: State, input: Lines) -> String {
-    let mut tail_positions: HashSet<Position> = HashSet::new();
-    for command in input.flat_map(Command::parse) {
+fn run(mut state: State, input: Lines) -> Result<usize> {
+    let mut tail_positions: HashSet<Pos> = HashSet::new();
+    for command in parse_lines(input, Command::parse)? {
         for _ in 0..command.count {
             state = state.next(&command.offset);
+            trace(format_args!("move {}", command.offset), &state);
             tail_positions.insert(*state.tail());
         }
     }
-    tail_positions.len().to_string()
+    Ok(tail_positions.len())
 }
 
-fn part1(input: Lines) -> String {
-    run(State::new(2), input)
+fn part1(input: Lines) -> Result<Answer> {
+    Ok(run(State::new(2), input)?.into())
 }
 
-fn part2(input: Lines) -> String {
-    run(State::new(10), input)
+fn part2(input: Lines) -> Result<Answer> {
+    Ok(run(State::new(10), input)?.into())
 }
 
-fn main() {
+pub fn run_parts(runner: &Runner) {
     let input = include_str!("input.txt");
-    let runner: &Runner = create_runner!();
+    runner.known_answers(include_str!("answers.txt"));
     runner.run(named!(part1), input);
     runner.run(named!(part2), input);
 }
 
+fn main() {
+    run_parts(create_runner!());
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use advent_of_code::verify;
+    use advent_of_code::{verify, Error};
+
+    #[test]
+    fn test_render() {
+        let state = State::new(3)
+            .next(&Pos::new(1, 0))
+            .next(&Pos::new(1, 0))
+            .next(&Pos::new(0, 1));
+        assert_eq!(state.render(), vec!["..H", "21."]);
+        assert_eq!(State::new(2).render(), vec!["H"]);
+        let state = State::new(2).next(&Pos::new(0, -1));
+        assert_eq!(state.render(), vec!["T", "H"]);
+    }
 
     #[test]
     fn example() {
         let input = include_str!("example.txt");
-        verify!(part1, input, "13");
-        verify!(part2, input, "1");
+        verify!(part1, input, 13);
+        verify!(part2, input, 1);
+    }
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            part1("R 4\nX 2".lines()),
+            Err(Error::parse(2, "expected `^([RLUD]) (\\d+)$`, got \"X 2\""))
+        );
     }
 }
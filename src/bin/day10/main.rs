@@ -1,63 +1,82 @@
-use advent_of_code::{create_runner, named, Named, Runner};
+use advent_of_code::{
+    captures, create_runner, named, parse_lines, Answer, LineError, Named, Result, Runner,
+};
 use itertools::Itertools;
+use lazy_regex::regex;
 use std::str::Lines;
 
-fn parse_program(input: Lines) -> Vec<i64> {
-    input
-        .flat_map(|line| {
-            if line == "noop" {
-                vec![0]
-            } else {
-                vec![0, line[5..].parse::<i64>().unwrap()]
-            }
-        })
-        .collect::<Vec<_>>()
+/// The change to `x` during each cycle of an instruction.
+fn parse_instruction(line: &str) -> Result<Vec<i64>, LineError> {
+    if line == "noop" {
+        return Ok(vec![0]);
+    }
+    let (dx,) = captures::<(i64,)>(regex!(r"^addx (-?\d+)$"), line)?;
+    Ok(vec![0, dx])
+}
+
+fn parse_program(input: Lines) -> Result<Vec<i64>> {
+    Ok(parse_lines(input, parse_instruction)?.concat())
 }
 
-fn part1(input: Lines) -> String {
-    let instructions = parse_program(input);
+fn part1(input: Lines) -> Result<Answer> {
+    let instructions = parse_program(input)?;
     let cycles = instructions.iter().enumerate().scan(1, |x, (cycle, dx)| {
         let result = *x * (cycle + 1) as i64;
         *x += dx;
         Some(result)
     });
-    cycles.skip(19).step_by(40).take(6).sum::<i64>().to_string()
+    Ok(cycles.skip(19).step_by(40).take(6).sum::<i64>().into())
 }
 
-fn part2(input: Lines) -> String {
-    let instructions = parse_program(input);
+fn part2(input: Lines) -> Result<Answer> {
+    let instructions = parse_program(input)?;
     let pixels = (0..40i64).cycle();
     let cycles = instructions.iter().zip(pixels).scan(1, |x, (dx, pixel)| {
         let draw = if pixel.abs_diff(*x) <= 1 { '#' } else { '.' };
         *x += dx;
         Some(draw)
     });
-    cycles
+    let rows = cycles
         .chunks(40)
         .into_iter()
         .map(|c| c.collect::<String>())
         .take(6)
-        .chain(std::iter::once(String::new()))
-        .join("\n")
+        .collect_vec();
+    Ok(Answer::letters(rows))
 }
 
-fn main() {
+pub fn run_parts(runner: &Runner) {
     let input = include_str!("input.txt");
-    let runner: &Runner = create_runner!();
+    runner.known_answers(include_str!("answers.txt"));
     runner.run(named!(part1), input);
     runner.run(named!(part2), input);
 }
 
+fn main() {
+    run_parts(create_runner!());
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use advent_of_code::verify;
+    use advent_of_code::{verify, Error};
 
     #[test]
     fn example() {
         let input = include_str!("example.txt");
-        verify!(part1, input, "13140");
+        verify!(part1, input, 13140);
         let expected = include_str!("part2_expected.txt");
-        verify!(part2, input, expected);
+        verify!(part2, input, Answer::grid(expected));
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            part1("noop\naddx 3\naddx x".lines()),
+            Err(Error::parse(
+                3,
+                "expected `^addx (-?\\d+)$`, got \"addx x\""
+            ))
+        );
     }
 }
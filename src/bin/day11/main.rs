@@ -1,6 +1,8 @@
-use advent_of_code::{create_runner, named, Named, Runner};
+use advent_of_code::{
+    captures, create_runner, named, sections, Answer, LineError, Named, Result, Runner,
+};
 use itertools::Itertools;
-use lazy_regex::regex_captures;
+use lazy_regex::regex;
 use std::str::Lines;
 
 type WorryLevel = u64;
@@ -13,31 +15,33 @@ struct MonkeyBehavior {
 }
 
 impl MonkeyBehavior {
-    fn parse(input: String) -> Option<Self> {
-        let (_, starting_items, op, arg, divisible_by, to_true, to_false) = regex_captures!(
-            r"Monkey \d+:
+    fn parse(input: &str) -> Result<Self, LineError> {
+        let (starting_items, op, arg, divisible_by, to_true, to_false) =
+            captures::<(String, char, String, WorryLevel, usize, usize)>(
+                regex!(
+                    r"Monkey \d+:
   Starting items: ([0-9, ]+)
   Operation: new = old (\+|\*) (old|\d+)
   Test: divisible by (\d+)
     If true: throw to monkey (\d+)
-    If false: throw to monkey (\d+)",
-            &input
-        )?;
+    If false: throw to monkey (\d+)"
+                ),
+                input,
+            )?;
         let starting_items = starting_items
             .split(", ")
-            .map(|x| x.parse::<WorryLevel>().unwrap())
-            .collect_vec();
+            .map(|x| {
+                x.parse::<WorryLevel>()
+                    .map_err(|_| LineError::new(format!("expected a worry level, got {x:?}")))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
         let operation: Box<dyn Fn(WorryLevel) -> WorryLevel> =
             match (op, arg.parse::<WorryLevel>().ok()) {
-                ("+", None) => Box::new(|x| x + x),
-                ("+", Some(y)) => Box::new(move |x| x + y),
-                ("*", None) => Box::new(|x| x * x),
-                ("*", Some(y)) => Box::new(move |x| x * y),
-                _ => panic!("unexpected op={op}"),
+                ('+', None) => Box::new(|x| x + x),
+                ('+', Some(y)) => Box::new(move |x| x + y),
+                (_, None) => Box::new(|x| x * x),
+                (_, Some(y)) => Box::new(move |x| x * y),
             };
-        let divisible_by = divisible_by.parse::<WorryLevel>().unwrap();
-        let to_true = to_true.parse::<usize>().unwrap();
-        let to_false = to_false.parse::<usize>().unwrap();
         let throw_to = Box::new(move |x| {
             if x % divisible_by == 0 {
                 to_true
@@ -45,7 +49,7 @@ impl MonkeyBehavior {
                 to_false
             }
         });
-        Some(Self {
+        Ok(Self {
             starting_items,
             operation,
             divisible_by,
@@ -53,13 +57,11 @@ impl MonkeyBehavior {
         })
     }
 
-    fn parse_all(input: Lines) -> Vec<Self> {
-        input
-            .group_by(|line| line.is_empty())
-            .into_iter()
-            .flat_map(|(empty, mut lines)| if empty { None } else { Some(lines.join("\n")) })
-            .flat_map(Self::parse)
-            .collect_vec()
+    fn parse_all(input: Lines) -> Result<Vec<Self>> {
+        sections(input)
+            .iter()
+            .map(|section| section.parse_text(Self::parse))
+            .collect()
     }
 }
 
@@ -127,51 +129,45 @@ impl KeepAway {
         let mut inspected = self.inspected();
         inspected.sort();
         inspected.reverse();
-        println!("inspected={inspected:?}");
         inspected.iter().take(2).product::<usize>()
     }
 }
 
-fn part1(input: Lines) -> String {
-    let behavior = MonkeyBehavior::parse_all(input);
-    for (index, monkey) in behavior.iter().enumerate() {
-        let items = &monkey.starting_items;
-        println!("Monkey {index}: {items:?}");
-    }
+fn part1(input: Lines) -> Result<Answer> {
+    let behavior = MonkeyBehavior::parse_all(input)?;
     let manage_worry = |x| x / 3;
     let mut keep_away = KeepAway::new(behavior);
     for _ in 0..20 {
         keep_away.do_round(&manage_worry)
     }
-    keep_away.monkey_business().to_string()
+    Ok(keep_away.monkey_business().into())
 }
 
-fn part2(input: Lines) -> String {
-    let behavior = MonkeyBehavior::parse_all(input);
-    for (index, monkey) in behavior.iter().enumerate() {
-        let items = &monkey.starting_items;
-        println!("Monkey {index}: {items:?}");
-    }
+fn part2(input: Lines) -> Result<Answer> {
+    let behavior = MonkeyBehavior::parse_all(input)?;
     let divisble_by_product = behavior
         .iter()
         .map(|b| b.divisible_by)
         .product::<WorryLevel>();
-    println!("divisble_by_product={divisble_by_product}");
     let manage_worry = move |x| x % divisble_by_product;
     let mut keep_away = KeepAway::new(behavior);
     for _ in 0..10000 {
         keep_away.do_round(&manage_worry)
     }
-    keep_away.monkey_business().to_string()
+    Ok(keep_away.monkey_business().into())
 }
 
-fn main() {
+pub fn run_parts(runner: &Runner) {
     let input = include_str!("input.txt");
-    let runner: &Runner = create_runner!();
+    runner.known_answers(include_str!("answers.txt"));
     runner.run(named!(part1), input);
     runner.run(named!(part2), input);
 }
 
+fn main() {
+    run_parts(create_runner!());
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -180,7 +176,7 @@ mod tests {
     #[test]
     fn example() {
         let input = include_str!("example.txt");
-        verify!(part1, input, "10605");
-        verify!(part2, input, "2713310158");
+        verify!(part1, input, 10605);
+        verify!(part2, input, 2713310158i64);
     }
 }
@@ -1,166 +1,113 @@
-use advent_of_code::{create_runner, named, Named, Runner};
-use itertools::Itertools;
+use advent_of_code::{
+    bfs_multi, create_runner, named, snapshot, Answer, Error, Grid, Image, Named, Pos, Result,
+    Runner, ToImage,
+};
 use std::str::Lines;
 
-#[derive(Clone)]
-struct Pos {
-    r: usize,
-    c: usize,
-}
-
-impl Pos {
-    fn new(r: usize, c: usize) -> Self {
-        Self { r, c }
-    }
-}
-
 struct HeightMap {
-    elevation: Vec<Vec<u8>>,
-    start: Pos,
-    end: Pos,
+    elevation: Grid<u8>,
+    start: Pos<usize>,
+    end: Pos<usize>,
 }
 
 impl HeightMap {
-    fn parse(input: Lines) -> Self {
-        let mut start = None;
-        let mut end = None;
-        let elevation = input
-            .into_iter()
-            .enumerate()
-            .map(|(r, elevations)| {
-                elevations
-                    .chars()
-                    .into_iter()
-                    .enumerate()
-                    .map(|(c, elevation)| match elevation {
-                        'S' => {
-                            start = Some(Pos::new(r, c));
-                            0
-                        }
-                        'E' => {
-                            end = Some(Pos::new(r, c));
-                            25
-                        }
-                        _ => elevation as u8 - b'a',
-                    })
-                    .collect_vec()
-            })
-            .collect_vec();
-        Self {
+    fn parse(input: Lines) -> Result<Self> {
+        let tiles = Grid::parse(input, |c| matches!(c, 'S' | 'E' | 'a'..='z').then_some(c))?;
+        let start = tiles
+            .position(|&c| c == 'S')
+            .ok_or_else(|| Error::InvalidInput("no start S".to_string()))?;
+        let end = tiles
+            .position(|&c| c == 'E')
+            .ok_or_else(|| Error::InvalidInput("no end E".to_string()))?;
+        let elevation = tiles.map(|&c| match c {
+            'S' => 0,
+            'E' => 25,
+            _ => c as u8 - b'a',
+        });
+        Ok(Self {
             elevation,
-            start: start.unwrap(),
-            end: end.unwrap(),
-        }
-    }
-
-    fn elevation(&self, pos: &Pos) -> u8 {
-        self.elevation[pos.r][pos.c]
-    }
-
-    fn possible_moves(&self, from: Pos) -> impl Iterator<Item = Pos> + '_ {
-        let from_elevation = self.elevation(&from);
-        [
-            if from.c > 0 {
-                Some(Pos::new(from.r, from.c - 1))
-            } else {
-                None
-            },
-            if from.c + 1 < self.elevation[0].len() {
-                Some(Pos::new(from.r, from.c + 1))
-            } else {
-                None
-            },
-            if from.r > 0 {
-                Some(Pos::new(from.r - 1, from.c))
-            } else {
-                None
-            },
-            if from.r + 1 < self.elevation.len() {
-                Some(Pos::new(from.r + 1, from.c))
-            } else {
-                None
-            },
-        ]
-        .into_iter()
-        .flatten()
-        .flat_map(move |p| {
-            Some(p).filter(|p| self.elevation(p).saturating_sub(from_elevation) <= 1)
+            start,
+            end,
         })
     }
 
-    fn all_points_with_elevation(&self, query: u8) -> impl Iterator<Item = Pos> + '_ {
-        self.elevation.iter().enumerate().flat_map(move |(r, row)| {
-            row.iter().enumerate().flat_map(move |(c, elevation)| {
-                if *elevation == query {
-                    Some(Pos::new(r, c))
-                } else {
-                    None
-                }
-            })
-        })
+    fn possible_moves(&self, from: Pos<usize>) -> impl Iterator<Item = Pos<usize>> + '_ {
+        let from_elevation = self.elevation[from];
+        self.elevation
+            .neighbors4(from)
+            .filter(move |&p| self.elevation[p].saturating_sub(from_elevation) <= 1)
     }
-}
 
-struct Solver<'a> {
-    map: &'a HeightMap,
-    steps: Vec<Vec<u16>>,
-}
-
-impl<'a> Solver<'a> {
-    fn new(map: &'a HeightMap) -> Self {
-        let steps = map
-            .elevation
+    fn all_points_with_elevation(&self, query: u8) -> impl Iterator<Item = Pos<usize>> + '_ {
+        self.elevation
             .iter()
-            .map(|row| row.iter().map(|_| u16::MAX).collect_vec())
-            .collect_vec();
-        Self { map, steps }
+            .filter(move |(_, &elevation)| elevation == query)
+            .map(|(pos, _)| pos)
     }
 
-    fn find_shortest_len(&mut self) -> u16 {
-        self.find_shortest_len_from(self.map.start.clone())
+    /// A shortest path to the end from the nearest of `starts`, both included.
+    fn shortest_path(
+        &self,
+        starts: impl IntoIterator<Item = Pos<usize>>,
+    ) -> Option<Vec<Pos<usize>>> {
+        bfs_multi(starts, |&pos| self.possible_moves(pos)).path(&self.end)
     }
 
-    fn find_shortest_len_from(&mut self, pos: Pos) -> u16 {
-        self.find_shortest_len_internal(pos, 0);
-        self.steps[self.map.end.r][self.map.end.c]
+    /// Steps along the shortest path, saving a picture of it when exporting.
+    fn shortest_path_len(&self, starts: impl IntoIterator<Item = Pos<usize>>) -> Option<usize> {
+        let path = self.shortest_path(starts)?;
+        snapshot(&Route {
+            map: self,
+            path: &path,
+        });
+        Some(path.len() - 1)
     }
+}
+
+/// The height map from green valleys to white peaks, with a path in red.
+struct Route<'a> {
+    map: &'a HeightMap,
+    path: &'a [Pos<usize>],
+}
 
-    fn find_shortest_len_internal(&mut self, pos: Pos, length: u16) {
-        if length < self.steps[pos.r][pos.c] {
-            self.steps[pos.r][pos.c] = length;
-            let new_length = length + 1;
-            for new_pos in self.map.possible_moves(pos) {
-                self.find_shortest_len_internal(new_pos, new_length)
-            }
+impl ToImage for Route<'_> {
+    fn to_image(&self) -> Image {
+        let mut image = Image::from_grid(&self.map.elevation, |&elevation| {
+            let shade = (elevation as u32 * 255 / 25) as u8;
+            [shade, 128 + shade / 2, shade]
+        });
+        for &pos in self.path {
+            image.set(pos, [220, 40, 40]);
         }
+        image.scale(4)
     }
 }
 
-fn part1(input: Lines) -> String {
-    let map = HeightMap::parse(input);
-    let mut solver = Solver::new(&map);
-    solver.find_shortest_len().to_string()
+fn part1(input: Lines) -> Result<Answer> {
+    let map = HeightMap::parse(input)?;
+    map.shortest_path_len([map.start])
+        .map(Answer::from)
+        .ok_or_else(|| Error::NoSolution("end is not reachable from start".to_string()))
 }
 
-fn part2(input: Lines) -> String {
-    let map = HeightMap::parse(input);
-    map.all_points_with_elevation(0)
-        .map(|p| {
-            let mut solver = Solver::new(&map);
-            solver.find_shortest_len_from(p)
-        })
-        .min()
-        .unwrap()
-        .to_string()
+fn part2(input: Lines) -> Result<Answer> {
+    let map = HeightMap::parse(input)?;
+    map.shortest_path_len(map.all_points_with_elevation(0))
+        .map(Answer::from)
+        .ok_or_else(|| Error::NoSolution("end is not reachable from elevation a".to_string()))
 }
 
-fn main() {
+pub fn run_parts(runner: &Runner) {
     let input = include_str!("input.txt");
-    let runner: &Runner = create_runner!();
+    runner.known_answers(include_str!("answers.txt"));
     runner.run(named!(part1), input);
     runner.run(named!(part2), input);
 }
 
+fn main() {
+    run_parts(create_runner!());
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -169,7 +116,7 @@ mod tests {
     #[test]
     fn example() {
         let input = include_str!("example.txt");
-        verify!(part1, input, "31");
-        verify!(part2, input, "29");
+        verify!(part1, input, 31);
+        verify!(part2, input, 29);
     }
 }
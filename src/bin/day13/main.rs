@@ -1,4 +1,6 @@
-use advent_of_code::{create_runner, named, Named, Runner};
+use advent_of_code::{
+    create_runner, named, sections, Answer, Error, LineError, Named, Result, Runner,
+};
 use itertools::Itertools;
 use logos::{Lexer, Logos};
 use std::{cmp::Ordering, str::Lines};
@@ -25,19 +27,64 @@ enum Packet {
     Number(u64),
 }
 
-fn parse_tokens(tokens: &mut Lexer<Token>) -> Option<Packet> {
+/// The column of the token just read, counting chars from 1.
+fn column(tokens: &Lexer<Token>) -> usize {
+    tokens.source()[..tokens.span().start].chars().count() + 1
+}
+
+/// The next packet, or `None` at the `]` which ends the enclosing list.
+fn parse_tokens(tokens: &mut Lexer<Token>) -> Result<Option<Packet>, LineError> {
+    match tokens.next() {
+        Some(Token::Number(x)) => Ok(Some(Packet::Number(x))),
+        Some(Token::BeginList) => {
+            let mut list = Vec::new();
+            while let Some(packet) = parse_tokens(tokens)? {
+                list.push(packet);
+            }
+            Ok(Some(Packet::List(list)))
+        }
+        Some(Token::EndList) => Ok(None),
+        Some(Token::Error) => Err(LineError::at(
+            column(tokens),
+            format!("unexpected {:?}", tokens.slice()),
+        )),
+        None => Err(LineError::at(
+            tokens.source().chars().count() + 1,
+            "the packet ends before its lists are closed",
+        )),
+    }
+}
+
+fn parse(line: &str) -> Result<Packet, LineError> {
+    let mut tokens = Token::lexer(line);
+    let packet = parse_tokens(&mut tokens)?
+        .ok_or_else(|| LineError::at(column(&tokens), "expected a packet, got \"]\""))?;
     match tokens.next() {
-        Some(Token::Number(x)) => Some(Packet::Number(x)),
-        Some(Token::BeginList) => Some(Packet::List(
-            std::iter::from_fn(|| parse_tokens(tokens)).collect_vec(),
+        None => Ok(packet),
+        Some(_) => Err(LineError::at(
+            column(&tokens),
+            format!("unexpected {:?} after the packet", tokens.slice()),
         )),
-        _ => None,
     }
 }
 
-fn parse(line: &str) -> Option<Packet> {
-    let mut lex = Token::lexer(line);
-    parse_tokens(&mut lex)
+/// The pairs of packets, which are separated by blank lines.
+fn parse_pairs(input: Lines) -> Result<Vec<(Packet, Packet)>> {
+    sections(input)
+        .iter()
+        .map(|section| {
+            section
+                .parse_lines(parse)?
+                .into_iter()
+                .collect_tuple()
+                .ok_or_else(|| {
+                    Error::parse(
+                        section.first_line,
+                        format!("expected a pair of packets, got {}", section.lines.len()),
+                    )
+                })
+        })
+        .collect()
 }
 
 impl Ord for Packet {
@@ -57,38 +104,30 @@ impl PartialOrd for Packet {
     }
 }
 
-fn part1(input: Lines) -> String {
-    input
-        .map(parse)
-        .group_by(|p| p.is_some())
+fn part1(input: Lines) -> Result<Answer> {
+    Ok(parse_pairs(input)?
         .into_iter()
-        .filter_map(|(is_some, packets)| {
-            if is_some {
-                Some(packets.flatten().collect_vec())
-            } else {
-                None
-            }
-        })
         .enumerate()
-        .filter_map(|(index, packets)| {
-            if packets[0].cmp(&packets[1]) == Ordering::Less {
+        .filter_map(|(index, (left, right))| {
+            if left.cmp(&right) == Ordering::Less {
                 Some(index + 1)
             } else {
                 None
             }
         })
         .sum::<usize>()
-        .to_string()
+        .into())
 }
 
-fn part2(input: Lines) -> String {
+fn part2(input: Lines) -> Result<Answer> {
     let dividers = vec![parse("[[2]]").unwrap(), parse("[[6]]").unwrap()];
-    let mut packets = input
-        .filter_map(parse)
+    let mut packets = parse_pairs(input)?
+        .into_iter()
+        .flat_map(|(left, right)| [left, right])
         .chain(dividers.clone())
         .collect_vec();
     packets.sort();
-    packets
+    Ok(packets
         .into_iter()
         .enumerate()
         .filter_map(|(index, p)| {
@@ -99,16 +138,20 @@ fn part2(input: Lines) -> String {
             }
         })
         .product::<usize>()
-        .to_string()
+        .into())
 }
 
-fn main() {
+pub fn run_parts(runner: &Runner) {
     let input = include_str!("input.txt");
-    let runner: &Runner = create_runner!();
+    runner.known_answers(include_str!("answers.txt"));
     runner.run(named!(part1), input);
     runner.run(named!(part2), input);
 }
 
+fn main() {
+    run_parts(create_runner!());
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -117,7 +160,31 @@ mod tests {
     #[test]
     fn example() {
         let input = include_str!("example.txt");
-        verify!(part1, input, "13");
-        verify!(part2, input, "140");
+        verify!(part1, input, 13);
+        verify!(part2, input, 140);
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            part1("[1]\n[2]\n\n[1,[2]".lines()),
+            Err(Error::parse_at(
+                4,
+                7,
+                "the packet ends before its lists are closed"
+            ))
+        );
+        assert_eq!(
+            part1("[1]\n[x]".lines()),
+            Err(Error::parse_at(2, 2, "unexpected \"x\""))
+        );
+        assert_eq!(
+            part1("[1]\n[2]]".lines()),
+            Err(Error::parse_at(2, 4, "unexpected \"]\" after the packet"))
+        );
+        assert_eq!(
+            part2("[1]\n[2]\n\n[3]".lines()),
+            Err(Error::parse(4, "expected a pair of packets, got 1"))
+        );
     }
 }
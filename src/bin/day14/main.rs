@@ -1,39 +1,27 @@
-use advent_of_code::{create_runner, named, Named, Runner};
+use advent_of_code::{
+    create_runner, named, parse_lines, snapshot, trace, Answer, Error, Grid, Image, LineError,
+    Named, Render, Result, Runner, ToImage,
+};
 use itertools::Itertools;
-use std::fmt::Display;
 use std::ops::Range;
 use std::str::Lines;
 
-#[derive(Clone, Debug, PartialEq, Eq)]
-struct Pos {
-    x: usize,
-    y: usize,
-}
-
-impl Display for Pos {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "({},{})", self.x, self.y)
-    }
-}
-
-impl Pos {
-    fn new(x: usize, y: usize) -> Self {
-        Self { x, y }
-    }
-    fn parse(input: &str) -> Option<Self> {
-        let (x, y) = input.split_once(',')?;
-        let x = x.parse::<usize>().ok()?;
-        let y = y.parse::<usize>().ok()?;
-        Some(Self { x, y })
-    }
-}
+type Pos = advent_of_code::Pos<usize>;
 
-fn parse_line(input: &str) -> Vec<Pos> {
-    input
+/// The corners of a path of rock, each segment horizontal or vertical.
+fn parse_line(input: &str) -> Result<Vec<Pos>, LineError> {
+    let points = input
         .split(" -> ")
-        .into_iter()
-        .flat_map(Pos::parse)
-        .collect_vec()
+        .map(|pos| Ok(pos.parse::<Pos>()?))
+        .collect::<Result<Vec<_>, LineError>>()?;
+    for (a, b) in points.iter().tuple_windows() {
+        if a.x != b.x && a.y != b.y {
+            return Err(LineError::new(format!(
+                "segment from {a} to {b} is not horizontal or vertical"
+            )));
+        }
+    }
+    Ok(points)
 }
 
 struct PosBounds {
@@ -42,40 +30,40 @@ struct PosBounds {
 }
 
 impl PosBounds {
-    fn from_segments(segments: &[Vec<Pos>]) -> Self {
-        let (min_x, max_x) = segments
-            .iter()
-            .flat_map(|s| s.iter().map(|p| p.x))
-            .minmax()
-            .into_option()
-            .unwrap();
-        let (min_y, max_y) = segments
-            .iter()
-            .flat_map(|s| s.iter().map(|p| p.y))
-            .minmax()
-            .into_option()
-            .unwrap();
-        Self {
+    fn from_segments(segments: &[Vec<Pos>]) -> Result<Self> {
+        let points = || segments.iter().flatten();
+        let (Some((min_x, max_x)), Some((min_y, max_y))) = (
+            points().map(|p| p.x).minmax().into_option(),
+            points().map(|p| p.y).minmax().into_option(),
+        ) else {
+            return Err(Error::InvalidInput("the cave has no rock".to_string()));
+        };
+        Ok(Self {
             min: Pos::new(min_x, min_y),
             max: Pos::new(max_x, max_y),
-        }
+        })
     }
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Tile {
+    Air,
+    Rock,
+    Sand,
+}
+
 struct Cave {
     x_offset: usize,
-    cells: Vec<Vec<bool>>,
+    cells: Grid<Tile>,
 }
 
 impl Cave {
     fn new(x_offset: usize, x_size: usize, y_size: usize) -> Self {
-        let cells = std::iter::repeat(std::iter::repeat(false).take(x_size).collect_vec())
-            .take(y_size)
-            .collect_vec();
+        let cells = Grid::new(x_size, y_size, Tile::Air);
         Self { x_offset, cells }
     }
-    fn from_segments(segments: Vec<Vec<Pos>>) -> Self {
-        let bounds = PosBounds::from_segments(&segments);
+    fn from_segments(segments: Vec<Vec<Pos>>) -> Result<Self> {
+        let bounds = PosBounds::from_segments(&segments)?;
         let mut cave = Cave::new(
             bounds.min.x - 1,
             bounds.max.x + 3 - bounds.min.x,
@@ -86,15 +74,15 @@ impl Cave {
                 cave.mark_segment(a, b)
             }
         }
-        cave
+        Ok(cave)
     }
 
     fn get(&self, p: &Pos) -> bool {
-        self.cells[p.y][p.x - self.x_offset]
+        self.cells[Pos::new(p.x - self.x_offset, p.y)] != Tile::Air
     }
 
-    fn set(&mut self, p: &Pos) {
-        self.cells[p.y][p.x - self.x_offset] = true
+    fn set(&mut self, p: &Pos, tile: Tile) {
+        self.cells[Pos::new(p.x - self.x_offset, p.y)] = tile
     }
 
     fn range(a: usize, b: usize) -> Range<usize> {
@@ -105,17 +93,17 @@ impl Cave {
         }
     }
 
+    /// Marks a segment, which `parse_line` made sure is horizontal or
+    /// vertical.
     fn mark_segment(&mut self, a: &Pos, b: &Pos) {
         if a.x == b.x {
             Self::range(a.y, b.y)
                 .map(move |y| Pos { x: a.x, y })
-                .for_each(|p| self.set(&p))
-        } else if a.y == b.y {
+                .for_each(|p| self.set(&p, Tile::Rock))
+        } else {
             Self::range(a.x, b.x)
                 .map(move |x| Pos { x, y: a.y })
-                .for_each(|p| self.set(&p))
-        } else {
-            panic!("Line from {a} to {b} not horizontal or vertical")
+                .for_each(|p| self.set(&p, Tile::Rock))
         }
     }
 
@@ -127,60 +115,109 @@ impl Cave {
     }
 
     fn drop_one(&self, start: &Pos) -> Option<Pos> {
-        let mut p = start.clone();
+        let mut p = *start;
         if self.get(start) {
             return None;
         };
         while let Some(next_p) = self.drop_next(&p) {
-            if next_p.y >= self.cells.len() - 1 {
+            if next_p.y >= self.cells.height() - 1 {
                 return None;
             };
             p = next_p;
+            trace(
+                "sand falling",
+                &Falling {
+                    cave: self,
+                    sand: p,
+                },
+            );
         }
         Some(p)
     }
 
     fn drop_all(&mut self, p: &Pos) -> usize {
-        std::iter::repeat(p)
+        let count = std::iter::repeat(p)
             .map(|p| {
                 let last = self.drop_one(p);
-                last.iter().for_each(|np| self.set(np));
+                last.iter().for_each(|np| self.set(np, Tile::Sand));
                 last
             })
             .enumerate()
             .find_map(|(index, p)| if p.is_none() { Some(index) } else { None })
-            .unwrap()
+            .unwrap();
+        snapshot(self);
+        count
     }
 }
 
-fn part1(input: Lines) -> String {
-    let segments = input.map(parse_line).collect_vec();
-    Cave::from_segments(segments)
+impl Render for Cave {
+    fn render(&self) -> Vec<String> {
+        self.cells.render(|tile| match tile {
+            Tile::Air => '.',
+            Tile::Rock => '#',
+            Tile::Sand => 'o',
+        })
+    }
+}
+
+impl ToImage for Cave {
+    fn to_image(&self) -> Image {
+        Image::from_grid(&self.cells, |tile| match tile {
+            Tile::Air => [24, 24, 32],
+            Tile::Rock => [128, 128, 128],
+            Tile::Sand => [230, 190, 90],
+        })
+        .scale(4)
+    }
+}
+
+/// The cave with a unit of sand which has not come to rest yet.
+struct Falling<'a> {
+    cave: &'a Cave,
+    sand: Pos,
+}
+
+impl Render for Falling<'_> {
+    fn render(&self) -> Vec<String> {
+        let mut rows = self.cave.render();
+        let x = self.sand.x - self.cave.x_offset;
+        rows[self.sand.y].replace_range(x..x + 1, "~");
+        rows
+    }
+}
+
+fn part1(input: Lines) -> Result<Answer> {
+    let segments = parse_lines(input, parse_line)?;
+    Ok(Cave::from_segments(segments)?
         .drop_all(&Pos::new(500, 0))
-        .to_string()
+        .into())
 }
 
-fn part2(input: Lines) -> String {
-    let mut segments = input.map(parse_line).collect_vec();
-    let bounds = PosBounds::from_segments(&segments);
+fn part2(input: Lines) -> Result<Answer> {
+    let mut segments = parse_lines(input, parse_line)?;
+    let bounds = PosBounds::from_segments(&segments)?;
     let start = Pos::new(500, 0);
     let bottom = bounds.max.y + 2;
     segments.push(vec![
         Pos::new(start.x - bottom, bottom),
         Pos::new(start.x + bottom, bottom),
     ]);
-    Cave::from_segments(segments)
+    Ok(Cave::from_segments(segments)?
         .drop_all(&Pos::new(500, 0))
-        .to_string()
+        .into())
 }
 
-fn main() {
+pub fn run_parts(runner: &Runner) {
     let input = include_str!("input.txt");
-    let runner: &Runner = create_runner!();
+    runner.known_answers(include_str!("answers.txt"));
     runner.run(named!(part1), input);
     runner.run(named!(part2), input);
 }
 
+fn main() {
+    run_parts(create_runner!());
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -189,8 +226,20 @@ mod tests {
     #[test]
     fn segment_parse() {
         assert_eq!(
-            parse_line("1,2 -> 3,4"),
-            vec!(Pos::new(1, 2), Pos::new(3, 4))
+            parse_line("1,2 -> 3,2"),
+            Ok(vec!(Pos::new(1, 2), Pos::new(3, 2)))
+        );
+        assert!(parse_line("1,2 -> 3").is_err());
+        assert_eq!(
+            parse_lines("1,2 -> 1,4\n1,2 -> 3,4".lines(), parse_line),
+            Err(Error::parse(
+                2,
+                "segment from 1,2 to 3,4 is not horizontal or vertical"
+            ))
+        );
+        assert_eq!(
+            part1("".lines()),
+            Err(Error::InvalidInput("the cave has no rock".to_string()))
         );
     }
 
@@ -198,7 +247,7 @@ mod tests {
     fn segment_horizontal() {
         let mut segments = vec![vec![Pos::new(1, 1), Pos::new(3, 1)]];
         for _ in 0..2 {
-            let cave = Cave::from_segments(segments.clone());
+            let cave = Cave::from_segments(segments.clone()).unwrap();
             assert!(cave.get(&Pos::new(1, 1)));
             assert!(cave.get(&Pos::new(2, 1)));
             assert!(cave.get(&Pos::new(3, 1)));
@@ -210,7 +259,7 @@ mod tests {
     fn segment_vertical() {
         let mut segments = vec![vec![Pos::new(1, 1), Pos::new(1, 3)]];
         for _ in 0..2 {
-            let cave = Cave::from_segments(segments.clone());
+            let cave = Cave::from_segments(segments.clone()).unwrap();
             assert!(cave.get(&Pos::new(1, 1)));
             assert!(cave.get(&Pos::new(1, 2)));
             assert!(cave.get(&Pos::new(1, 3)));
@@ -221,7 +270,7 @@ mod tests {
     #[test]
     fn drop_next() {
         let segments = vec![vec![Pos::new(1, 2), Pos::new(3, 2)]];
-        let cave = Cave::from_segments(segments);
+        let cave = Cave::from_segments(segments).unwrap();
         assert_eq!(
             cave.drop_next(&Pos::new(2, 0)),
             Some(Pos::new(2, 1)),
@@ -247,7 +296,7 @@ mod tests {
     #[test]
     fn drop_one() {
         let segments = vec![vec![Pos::new(1, 2), Pos::new(3, 2)]];
-        let mut cave = Cave::from_segments(segments);
+        let mut cave = Cave::from_segments(segments).unwrap();
         assert_eq!(
             cave.drop_one(&Pos::new(2, 0)),
             Some(Pos::new(2, 1)),
@@ -255,25 +304,31 @@ mod tests {
         );
         assert_eq!(cave.drop_one(&Pos::new(1, 0)), None, "falls left forever");
         assert_eq!(cave.drop_one(&Pos::new(3, 0)), None, "falls right forever");
-        cave.set(&Pos::new(2, 0));
+        cave.set(&Pos::new(2, 0), Tile::Sand);
         assert_eq!(cave.drop_one(&Pos::new(2, 0)), None, "start blocked");
     }
 
     #[test]
     fn drop_all() {
         let segments = vec![vec![Pos::new(1, 2), Pos::new(3, 2)]];
-        let mut cave = Cave::from_segments(segments);
+        let mut cave = Cave::from_segments(segments).unwrap();
         assert_eq!(
             cave.drop_all(&Pos::new(2, 0)),
             1,
             "one drop stays in center of short segment"
         );
+        assert_eq!(cave.render(), vec![".....", "..o..", ".###."]);
+        let falling = Falling {
+            cave: &cave,
+            sand: Pos::new(3, 1),
+        };
+        assert_eq!(falling.render(), vec![".....", "..o~.", ".###."]);
     }
 
     #[test]
     fn example() {
         let input = include_str!("example.txt");
-        verify!(part1, input, "24");
-        verify!(part2, input, "93");
+        verify!(part1, input, 24);
+        verify!(part2, input, 93);
     }
 }
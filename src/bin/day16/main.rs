@@ -1,9 +1,11 @@
-use advent_of_code::{create_runner, named, Named, Runner};
+use advent_of_code::{
+    all_pairs, captures, create_runner, named, parse_lines, AllPairs, Answer, LineError, Named,
+    Result, Runner,
+};
 use itertools::Itertools;
-use lazy_regex::regex_captures;
+use lazy_regex::regex;
 use std::{
     collections::{HashMap, HashSet},
-    iter::repeat,
     str::Lines,
 };
 
@@ -15,18 +17,16 @@ struct Valve {
 }
 
 impl Valve {
-    fn parse(line: &str) -> Option<Self> {
-        let (_, name, rate, tunnels) = regex_captures!(
-            r"Valve (\w+) has flow rate=(\d+); tunnels? leads? to valves? (.+)$",
-            line
+    fn parse(line: &str) -> Result<Self, LineError> {
+        let (name, rate, tunnels) = captures::<(String, usize, String)>(
+            regex!(r"Valve (\w+) has flow rate=(\d+); tunnels? leads? to valves? (.+)$"),
+            line,
         )?;
-        let name = name.to_owned();
-        let rate = rate.parse::<usize>().ok()?;
         let tunnels = tunnels
             .split(", ")
             .map(|s| (s.to_owned(), 1))
             .collect::<HashMap<_, _>>();
-        Some(Self {
+        Ok(Self {
             name,
             rate,
             tunnels,
@@ -34,84 +34,17 @@ impl Valve {
     }
 }
 
-struct Distance {
-    indices: HashMap<String, usize>,
-    distance: Vec<Vec<usize>>,
-}
-
-impl Distance {
-    fn new(names: Vec<String>) -> Self {
-        let n = names.len();
-        let mut distance = repeat(repeat(usize::MAX).take(n).collect_vec())
-            .take(n)
-            .collect_vec();
-        for (c, row) in distance.iter_mut().enumerate() {
-            row[c] = 0;
-        }
-        Self {
-            indices: names
-                .into_iter()
-                .enumerate()
-                .map(|(i, n)| (n, i))
-                .collect::<HashMap<_, _>>(),
-            distance,
-        }
-    }
-    fn add(&mut self, a: &str, b: &str, new_distance: usize) {
-        if let Some(a) = self.indices.get(a) {
-            if let Some(b) = self.indices.get(b) {
-                self.set_min(*a, *b, new_distance);
-            }
-        }
-    }
-    fn compute(&mut self) {
-        let n = self.indices.len();
-        for k in 0..n {
-            for i in 0..n {
-                for j in 0..n {
-                    self.set_min(
-                        i,
-                        j,
-                        self.distance[i][k].saturating_add(self.distance[k][j]),
-                    )
-                }
-            }
-        }
-    }
-    fn get_mut(&mut self, a: usize, b: usize) -> Option<&mut usize> {
-        self.distance.get_mut(a).and_then(|bs| bs.get_mut(b))
-    }
-    fn set_min(&mut self, a: usize, b: usize, new_distance: usize) {
-        if let Some(distance) = self.get_mut(a, b) {
-            if new_distance < *distance {
-                *distance = new_distance
-            }
-        }
-    }
-    fn dump(&self) {
-        for (a, &i) in self.indices.iter() {
-            for (b, &j) in self.indices.iter() {
-                let d = self.distance[i][j];
-                if d > 0 {
-                    println!("{a} -> {b} = {d}")
-                }
-            }
-        }
-    }
-    fn get(&self, a: &str, b: &str) -> usize {
-        self.distance[self.indices[a]][self.indices[b]]
-    }
-}
-
-fn distance_from_valves(valves: &HashMap<String, Valve>) -> Distance {
-    let mut distance = Distance::new(valves.keys().cloned().collect_vec());
-    for a in valves.values() {
-        for (b, d) in a.tunnels.iter() {
-            distance.add(&a.name, b, *d)
-        }
-    }
-    distance.compute();
-    distance
+type Distance<'a> = AllPairs<&'a str>;
+
+fn distance_from_valves(valves: &HashMap<String, Valve>) -> Distance<'_> {
+    all_pairs(valves.keys().map(String::as_str), |&name| {
+        valves[name]
+            .tunnels
+            .iter()
+            .filter(|(b, _)| valves.contains_key(*b))
+            .map(|(b, &d)| (b.as_str(), d))
+            .collect::<Vec<_>>()
+    })
 }
 
 #[derive(Debug)]
@@ -152,7 +85,7 @@ impl<'a> State<'a> {
         }
     }
 
-    fn all_unopened_splits(&'a self) -> impl Iterator<Item = (State, State)> + '_ {
+    fn all_unopened_splits(&'a self) -> impl Iterator<Item = (State<'a>, State<'a>)> + 'a {
         self.unopened.iter().powerset().map(|a| {
             let a = a.iter().map(|&&x| x).collect::<HashSet<_>>();
             let b = self
@@ -210,7 +143,10 @@ fn find_most_pressure_released(
         .iter()
         .flat_map(|&unopened| {
             state
-                .maybe_open(&valves[unopened], distance.get(state.valve, unopened))
+                .maybe_open(
+                    &valves[unopened],
+                    distance.distance(&state.valve, &unopened)?,
+                )
                 .map(|s| find_most_pressure_released(s, valves, distance))
         })
         .max()
@@ -218,50 +154,34 @@ fn find_most_pressure_released(
         .max(state.released)
 }
 
-fn part1(input: Lines) -> String {
-    let valve_vec = input.flat_map(Valve::parse).collect_vec();
-    let mut valves = valve_vec
+fn part1(input: Lines) -> Result<Answer> {
+    let mut valves = parse_lines(input, Valve::parse)?
         .into_iter()
         .map(|v| (v.name.clone(), v))
         .collect::<HashMap<_, _>>();
 
-    println!("original valves");
-    valves.iter().for_each(|v| println!("{v:?}"));
-
     let start = "AA";
     simplify_valves(&mut valves, start);
-    println!("simplified valves");
-    valves.iter().for_each(|v| println!("{v:?}"));
 
-    println!("distances");
     let distance = distance_from_valves(&valves);
-    distance.dump();
 
     let start = State::new(30, start, &valves);
-    find_most_pressure_released(start, &valves, &distance).to_string()
+    Ok(find_most_pressure_released(start, &valves, &distance).into())
 }
 
-fn part2(input: Lines) -> String {
-    let valve_vec = input.flat_map(Valve::parse).collect_vec();
-    let mut valves = valve_vec
+fn part2(input: Lines) -> Result<Answer> {
+    let mut valves = parse_lines(input, Valve::parse)?
         .into_iter()
         .map(|v| (v.name.clone(), v))
         .collect::<HashMap<_, _>>();
 
-    println!("original valves");
-    valves.iter().for_each(|v| println!("{v:?}"));
-
     let start = "AA";
     simplify_valves(&mut valves, start);
-    println!("simplified valves");
-    valves.iter().for_each(|v| println!("{v:?}"));
 
-    println!("distances");
     let distance = distance_from_valves(&valves);
-    distance.dump();
 
     let start = State::new(26, start, &valves);
-    start
+    Ok(start
         .all_unopened_splits()
         .map(|(human, elephant)| {
             let human = find_most_pressure_released(human, &valves, &distance);
@@ -270,16 +190,20 @@ fn part2(input: Lines) -> String {
         })
         .max()
         .unwrap_or(0)
-        .to_string()
+        .into())
 }
 
-fn main() {
+pub fn run_parts(runner: &Runner) {
     let input = include_str!("input.txt");
-    let runner: &Runner = create_runner!();
+    runner.known_answers(include_str!("answers.txt"));
     runner.run(named!(part1), input);
     runner.run(named!(part2), input);
 }
 
+fn main() {
+    run_parts(create_runner!());
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -289,7 +213,7 @@ mod tests {
     fn parse() {
         assert_eq!(
             Valve::parse("Valve AA has flow rate=0; tunnels lead to valves DD, BB"),
-            Some(Valve {
+            Ok(Valve {
                 name: "AA".to_owned(),
                 rate: 0,
                 tunnels: HashMap::from([("DD".to_owned(), 1), ("BB".to_owned(), 1)]),
@@ -297,7 +221,7 @@ mod tests {
         );
         assert_eq!(
             Valve::parse("Valve HH has flow rate=22; tunnel leads to valve GG"),
-            Some(Valve {
+            Ok(Valve {
                 name: "HH".to_owned(),
                 rate: 22,
                 tunnels: HashMap::from([("GG".to_owned(), 1)]),
@@ -308,7 +232,7 @@ mod tests {
     #[test]
     fn example() {
         let input = include_str!("example.txt");
-        verify!(part1, input, "1651");
-        verify!(part2, input, "1707");
+        verify!(part1, input, 1651);
+        verify!(part2, input, 1707);
     }
 }
@@ -1,79 +1,43 @@
-use advent_of_code::{create_runner, named, Named, Runner};
-use itertools::Itertools;
-use std::{
-    fmt::Display,
-    iter::{repeat, Cycle},
-    str::Lines,
+use advent_of_code::{
+    create_runner, extrapolate, named, snapshot, trace, Answer, Error, Grid, Image, Named, Render,
+    Result, Runner, ToImage,
 };
+use itertools::Itertools;
+use std::str::Lines;
 
-#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
-struct Pos {
-    x: usize,
-    y: usize,
-}
-
-impl Display for Pos {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "({},{})", self.x, self.y)
-    }
-}
-
-impl Pos {
-    fn new(x: usize, y: usize) -> Self {
-        Self { x, y }
-    }
-    fn push(&self, jet: Jet) -> Self {
-        let x = match jet {
-            Jet::Left => self.x - 1,
-            Jet::Right => self.x + 1,
-        };
-        Self { x, y: self.y }
-    }
-    fn drop(&self) -> Option<Self> {
-        Some(self.y).filter(|&y| y > 0).map(|y| Self {
-            x: self.x,
-            y: y - 1,
-        })
-    }
-}
-
-type Row = u8;
+type Pos = advent_of_code::Pos<usize>;
 
+/// Cells of a rock with `y` counting up from its bottom edge.
 #[derive(Clone, PartialEq, Eq, Debug)]
 struct RockShape {
-    rows: Vec<Row>,
-    size: Pos,
+    cells: Grid<bool>,
 }
 
 impl RockShape {
-    fn parse_all(input: Lines) -> Vec<Self> {
+    fn parse_all(input: Lines) -> Result<Vec<Self>> {
         input
             .group_by(|line| line.is_empty())
             .into_iter()
-            .flat_map(|(empty, lines)| {
-                if empty {
-                    None
-                } else {
-                    Some(Self::parse(lines))
-                }
-            })
-            .collect_vec()
+            .filter(|(empty, _)| !empty)
+            .map(|(_, lines)| Self::parse(lines))
+            .collect()
     }
 
-    fn parse<'a>(input: impl Iterator<Item = &'a str>) -> Self {
-        let mut input = input.peekable();
-        let width = input.peek().map(|line| line.len()).unwrap_or_default();
-        let mut rows = input.map(Self::parse_row).collect::<Vec<_>>();
-        rows.reverse();
-        let size = Pos::new(width, rows.len());
-        Self { rows, size }
+    fn parse<'a>(input: impl Iterator<Item = &'a str>) -> Result<Self> {
+        let top_down = Grid::parse(input, |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        })?;
+        let cells = Grid::from_rows(top_down.rows().rev().map(<[_]>::to_vec).collect());
+        Ok(Self { cells })
     }
 
-    fn parse_row(line: &str) -> Row {
-        line.chars()
-            .enumerate()
-            .map(|(i, c)| ((c == '#') as Row) << i)
-            .sum()
+    fn rocks(&self) -> impl Iterator<Item = Pos> + '_ {
+        self.cells
+            .iter()
+            .filter(|(_, &rock)| rock)
+            .map(|(pos, _)| pos)
     }
 }
 
@@ -84,102 +48,153 @@ enum Jet {
 }
 
 impl Jet {
-    fn parse_all(line: &str) -> Vec<Self> {
+    /// Parses the jet pattern, which is the first line of the input.
+    fn parse_all(line: &str) -> Result<Vec<Self>> {
         line.chars()
-            .flat_map(|c| match c {
-                '<' => Some(Self::Left),
-                '>' => Some(Self::Right),
-                _ => None,
+            .enumerate()
+            .map(|(index, c)| match c {
+                '<' => Ok(Self::Left),
+                '>' => Ok(Self::Right),
+                _ => Err(Error::parse_at(
+                    1,
+                    index + 1,
+                    format!("expected `<` or `>`, got {c:?}"),
+                )),
             })
-            .collect::<_>()
+            .collect()
     }
 }
 
 struct Generator<T> {
-    iter: Cycle<std::vec::IntoIter<T>>,
+    items: Vec<T>,
+    index: usize,
 }
 
 impl<T: Clone> Generator<T> {
     fn new(items: Vec<T>) -> Self {
-        Self {
-            iter: items.into_iter().cycle(),
-        }
+        Self { items, index: 0 }
     }
 
     fn get(&mut self) -> T {
-        self.iter.next().unwrap()
+        let item = self.items[self.index].clone();
+        self.index = (self.index + 1) % self.items.len();
+        item
     }
 }
 
+/// Cells of the chamber with `y` counting up from the floor.
 struct Chamber {
-    rows: Vec<Row>,
-    width: usize,
+    cells: Grid<bool>,
 }
 
 impl Chamber {
     fn new(width: usize) -> Self {
         Self {
-            rows: Vec::default(),
-            width,
+            cells: Grid::new(width, 0, false),
         }
     }
     fn height(&self) -> usize {
-        self.rows
-            .iter()
-            .enumerate()
-            .rev()
-            .find_map(|(i, &row)| if row != 0 { Some(i + 1) } else { None })
-            .unwrap_or_default()
+        self.cells
+            .rows()
+            .rposition(|row| row.contains(&true))
+            .map_or(0, |y| y + 1)
     }
     fn push_jet(&self, shape: &RockShape, pos: Pos, jet: Jet) -> Pos {
-        let new_pos = if match jet {
-            Jet::Left => pos.x > 0,
-            Jet::Right => pos.x + shape.size.x < self.width,
-        } {
-            Some(pos.push(jet))
-        } else {
-            None
+        let x = match jet {
+            Jet::Left => pos.x.checked_sub(1),
+            Jet::Right => Some(pos.x + 1).filter(|x| x + shape.cells.width() <= self.cells.width()),
         };
-        new_pos
+        x.map(|x| Pos::new(x, pos.y))
             .filter(|p| !self.intersects(shape, p))
             .unwrap_or(pos)
     }
+    /// Depth of the highest rock in each column below the top of the tower,
+    /// or the full height for empty columns.
+    fn surface(&self) -> Vec<usize> {
+        let height = self.height();
+        (0..self.cells.width())
+            .map(|x| {
+                self.cells
+                    .column(x)
+                    .take(height)
+                    .rev()
+                    .position(|&rock| rock)
+                    .unwrap_or(height)
+            })
+            .collect_vec()
+    }
     fn drop(&self, shape: &RockShape, pos: Pos) -> Option<Pos> {
-        pos.drop().filter(|p| !self.intersects(shape, p))
+        let y = pos.y.checked_sub(1)?;
+        Some(Pos::new(pos.x, y)).filter(|p| !self.intersects(shape, p))
     }
     fn intersects(&self, shape: &RockShape, pos: &Pos) -> bool {
-        self.rows[pos.y..pos.y + shape.rows.len()]
-            .iter()
-            .zip(shape.rows.iter())
-            .any(|(&chamber, &rock)| (rock << pos.x) & chamber != 0)
+        shape.rocks().any(|rock| self.cells[*pos + rock])
     }
     fn extend(&mut self, shape: &RockShape, pos: &Pos) {
-        let required = pos.y + shape.rows.len();
-        if self.rows.len() < required {
-            self.rows.extend(repeat(0).take(required - self.rows.len()))
-        }
+        self.cells.grow(pos.y + shape.cells.height(), false);
     }
     fn add(&mut self, shape: &RockShape, pos: &Pos) {
-        for (chamber, rock) in self.rows[pos.y..pos.y + shape.rows.len()]
-            .iter_mut()
-            .zip(shape.rows.iter())
-        {
-            *chamber |= rock << pos.x;
+        for rock in shape.rocks() {
+            self.cells[*pos + rock] = true;
         }
     }
-    #[allow(dead_code)]
-    fn dump(&self) {
-        for row in self.rows.iter().rev() {
-            let out: String = (0..self.width)
-                .map(|i| if (1 << i) & row != 0 { '#' } else { '.' })
-                .collect();
-            println!("|{out}|");
+}
+
+impl Render for Chamber {
+    fn render(&self) -> Vec<String> {
+        let mut rows = self
+            .cells
+            .render(|&rock| if rock { '#' } else { '.' })
+            .into_iter()
+            .rev()
+            .map(|row| format!("|{row}|"))
+            .collect_vec();
+        rows.push(format!("+{}+", "-".repeat(self.cells.width())));
+        rows
+    }
+}
+
+impl ToImage for Chamber {
+    fn to_image(&self) -> Image {
+        Image::from_grid(&self.cells, |&rock| {
+            if rock {
+                [200, 120, 60]
+            } else {
+                [24, 24, 32]
+            }
+        })
+        .flip_vertical()
+        .scale(4)
+    }
+}
+
+/// The chamber with a rock which has not come to rest yet, drawn as `@`.
+struct Falling<'a> {
+    chamber: &'a Chamber,
+    shape: &'a RockShape,
+    pos: Pos,
+}
+
+impl Render for Falling<'_> {
+    fn render(&self) -> Vec<String> {
+        let mut rows = self.chamber.render();
+        let top = self.chamber.cells.height() - 1;
+        for rock in self.shape.rocks() {
+            let Pos { x, y } = self.pos + rock;
+            // the left wall comes before the first column
+            rows[top - y].replace_range(x + 1..x + 2, "@");
         }
-        let bottom = str::repeat("-", self.width);
-        println!("+{bottom}+");
+        rows
     }
 }
 
+#[derive(PartialEq, Eq, Hash)]
+struct SimulationState {
+    rock_index: usize,
+    jet_index: usize,
+    surface: Vec<usize>,
+}
+
 struct Simulation {
     rock_shapes: Generator<RockShape>,
     jet_pattern: Generator<Jet>,
@@ -195,11 +210,21 @@ impl Simulation {
         }
     }
 
+    /// Once the next rock, next jet and surface of the tower repeat, every
+    /// following cycle of rocks adds the same height, so whole cycles are
+    /// skipped instead of simulated.
     fn drop_rocks(&mut self, count: usize) -> usize {
-        for _ in 0..count {
-            self.drop_rock()
+        extrapolate(self, count, Self::drop_rock, Self::state, |simulation| {
+            simulation.chamber.height() as i64
+        }) as usize
+    }
+
+    fn state(&self) -> SimulationState {
+        SimulationState {
+            rock_index: self.rock_shapes.index,
+            jet_index: self.jet_pattern.index,
+            surface: self.chamber.surface(),
         }
-        self.chamber.height()
     }
 
     fn drop_rock(&mut self) {
@@ -208,11 +233,16 @@ impl Simulation {
         self.chamber.extend(&shape, &pos);
         loop {
             pos = self.chamber.push_jet(&shape, pos, self.jet_pattern.get());
+            let falling = Falling {
+                chamber: &self.chamber,
+                shape: &shape,
+                pos,
+            };
+            trace("rock falling", &falling);
             if let Some(new_pos) = self.chamber.drop(&shape, pos) {
                 pos = new_pos;
             } else {
                 self.chamber.add(&shape, &pos);
-                //self.chamber.dump();
                 break;
             }
         }
@@ -226,26 +256,39 @@ impl Simulation {
     }
 }
 
-fn part1(mut input: Lines) -> String {
-    let rock_shapes = RockShape::parse_all(include_str!("rocks.txt").lines());
-    let jet_pattern = Jet::parse_all(input.next().unwrap());
+fn tower_height(mut input: Lines, num_rocks: usize) -> Result<Answer> {
+    let rock_shapes = RockShape::parse_all(include_str!("rocks.txt").lines())?;
+    let jet_pattern = Jet::parse_all(input.next().unwrap_or_default())?;
+    if jet_pattern.is_empty() {
+        return Err(Error::InvalidInput("no jet pattern".to_string()));
+    }
     let chamber = Chamber::new(7);
     let mut simulation = Simulation::new(rock_shapes, jet_pattern, chamber);
-    let num_rocks = 2022;
-    simulation.drop_rocks(num_rocks).to_string()
+    let height = simulation.drop_rocks(num_rocks);
+    // only the rocks until the cycle was found are actually in the chamber
+    snapshot(&simulation.chamber);
+    Ok(height.into())
 }
 
-fn part2(input: Lines) -> String {
-    input.take(0).count().to_string()
+fn part1(input: Lines) -> Result<Answer> {
+    tower_height(input, 2022)
 }
 
-fn main() {
+fn part2(input: Lines) -> Result<Answer> {
+    tower_height(input, 1000000000000)
+}
+
+pub fn run_parts(runner: &Runner) {
     let input = include_str!("input.txt");
-    let runner: &Runner = create_runner!();
+    runner.known_answers(include_str!("answers.txt"));
     runner.run(named!(part1), input);
     runner.run(named!(part2), input);
 }
 
+fn main() {
+    run_parts(create_runner!());
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -254,22 +297,70 @@ mod tests {
     #[test]
     fn parse_rocks() {
         assert_eq!(
-            RockShape::parse("#.\n.#".lines()),
+            RockShape::parse("#.\n.#".lines()).unwrap(),
             RockShape {
-                rows: vec![2, 1],
-                size: Pos::new(2, 2)
+                cells: Grid::from_rows(vec![vec![false, true], vec![true, false]])
             }
         );
         assert_eq!(
-            RockShape::parse_all(include_str!("rocks.txt").lines()).len(),
+            RockShape::parse_all(include_str!("rocks.txt").lines())
+                .unwrap()
+                .len(),
             5
         );
     }
 
+    #[test]
+    fn test_surface() {
+        let mut chamber = Chamber::new(3);
+        let shape = RockShape::parse("#.\n##".lines()).unwrap();
+        chamber.extend(&shape, &Pos::new(0, 0));
+        chamber.add(&shape, &Pos::new(0, 0));
+        assert_eq!(chamber.surface(), vec![0, 1, 2]);
+        assert_eq!(chamber.render(), vec!["|#..|", "|##.|", "+---+"]);
+        let shape = RockShape::parse("#".lines()).unwrap();
+        chamber.extend(&shape, &Pos::new(2, 2));
+        let falling = Falling {
+            chamber: &chamber,
+            shape: &shape,
+            pos: Pos::new(2, 2),
+        };
+        assert_eq!(falling.render(), vec!["|..@|", "|#..|", "|##.|", "+---+"]);
+    }
+
+    #[test]
+    fn test_drop_rocks_cycle() {
+        let input = include_str!("example.txt");
+        let rock_shapes = RockShape::parse_all(include_str!("rocks.txt").lines()).unwrap();
+        let jet_pattern = Jet::parse_all(input.lines().next().unwrap()).unwrap();
+        let new_simulation =
+            || Simulation::new(rock_shapes.clone(), jet_pattern.clone(), Chamber::new(7));
+        for count in [10, 100, 1000, 5000] {
+            let mut simulation = new_simulation();
+            (0..count).for_each(|_| simulation.drop_rock());
+            assert_eq!(
+                new_simulation().drop_rocks(count),
+                simulation.chamber.height()
+            );
+        }
+    }
+
     #[test]
     fn example() {
         let input = include_str!("example.txt");
-        verify!(part1, input, "3068");
-        verify!(part2, input, "0");
+        verify!(part1, input, 3068);
+        verify!(part2, input, 1514285714288i64);
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            part1("<<>x>".lines()),
+            Err(Error::parse_at(1, 4, "expected `<` or `>`, got 'x'"))
+        );
+        assert_eq!(
+            part1("".lines()),
+            Err(Error::InvalidInput("no jet pattern".to_string()))
+        );
     }
 }
@@ -1,101 +1,48 @@
-use advent_of_code::{create_runner, named, Named, Runner};
+use advent_of_code::{
+    create_runner, named, parse_lines, snapshot, Answer, Image, Named, Pos, Pos3, Result, Runner,
+    ToImage,
+};
 use std::{
     collections::{HashSet, VecDeque},
-    iter::repeat,
-    ops::Add,
-    str::{FromStr, Lines},
+    str::Lines,
 };
 
-type ScalarType = isize;
-
-#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
-struct Pos {
-    x: ScalarType,
-    y: ScalarType,
-    z: ScalarType,
-}
-
-impl Pos {
-    fn new(x: ScalarType, y: ScalarType, z: ScalarType) -> Pos {
-        Pos { x, y, z }
-    }
-}
-
-impl Add for Pos {
-    type Output = Self;
-
-    fn add(self, rhs: Self) -> Self::Output {
-        Self {
-            x: self.x + rhs.x,
-            y: self.y + rhs.y,
-            z: self.z + rhs.z,
-        }
-    }
+fn parse_coords(input: Lines) -> Result<HashSet<Pos3>> {
+    Ok(parse_lines(input, |line| Ok(line.parse::<Pos3>()?))?
+        .into_iter()
+        .collect())
 }
 
-#[derive(Debug, PartialEq, Eq)]
-struct ParsePosError;
-
-impl FromStr for Pos {
-    type Err = ParsePosError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (x, yz) = s.split_once(',').ok_or(ParsePosError)?;
-        let (y, z) = yz.split_once(',').ok_or(ParsePosError)?;
-
-        Ok(Self {
-            x: x.parse::<ScalarType>().map_err(|_| ParsePosError)?,
-            y: y.parse::<ScalarType>().map_err(|_| ParsePosError)?,
-            z: z.parse::<ScalarType>().map_err(|_| ParsePosError)?,
-        })
-    }
-}
+fn part1(input: Lines) -> Result<Answer> {
+    let coords = parse_coords(input)?;
 
-fn all_sides() -> Vec<Pos> {
-    vec![
-        Pos::new(-1, 0, 0),
-        Pos::new(1, 0, 0),
-        Pos::new(0, -1, 0),
-        Pos::new(0, 1, 0),
-        Pos::new(0, 0, -1),
-        Pos::new(0, 0, 1),
-    ]
-}
-
-fn part1(input: Lines) -> String {
-    let coords = input
-        .flat_map(|line| line.parse::<Pos>().ok())
-        .collect::<HashSet<Pos>>();
-
-    let sides = all_sides();
-    coords
+    Ok(coords
         .iter()
         .copied()
         .map(|pos| {
-            sides
+            Pos3::NEIGHBORS6
                 .iter()
                 .copied()
                 .filter(|&s| !coords.contains(&(pos + s)))
                 .count()
         })
         .sum::<usize>()
-        .to_string()
+        .into())
 }
 
 struct Matrix3<T: Copy> {
-    size: Pos,
+    size: Pos3,
     elements: Vec<T>,
 }
 
 impl<T: Copy> Matrix3<T> {
-    fn new(size: Pos, value: T) -> Self {
+    fn new(size: Pos3, value: T) -> Self {
         let num_elements = size.x as usize * size.y as usize * size.z as usize;
-        println!("Maxtrix3::new size={size:?} num_elements={num_elements}");
-        let elements = Vec::from_iter(repeat(value).take(num_elements));
+        let elements = Vec::from_iter(std::iter::repeat_n(value, num_elements));
         Self { size, elements }
     }
 
-    fn in_bounds(&self, pos: Pos) -> bool {
+    fn in_bounds(&self, pos: Pos3) -> bool {
         pos.x >= 0
             && pos.y >= 0
             && pos.z >= 0
@@ -104,37 +51,36 @@ impl<T: Copy> Matrix3<T> {
             && pos.z < self.size.z
     }
 
-    fn offset(&self, pos: Pos) -> usize {
+    fn offset(&self, pos: Pos3) -> usize {
         ((pos.z as usize) * (self.size.y as usize) + (pos.y as usize)) * (self.size.x as usize)
             + (pos.x as usize)
     }
-    fn get(&self, pos: Pos) -> Option<T> {
+    fn get(&self, pos: Pos3) -> Option<T> {
         if self.in_bounds(pos) {
             Some(self.elements[self.offset(pos)])
         } else {
             None
         }
     }
-    fn set(&mut self, pos: Pos, value: T) {
+    fn set(&mut self, pos: Pos3, value: T) {
         let offset = self.offset(pos);
         self.elements[offset] = value;
     }
 }
 
-fn mark_outside(coords: &HashSet<Pos>) -> Matrix3<bool> {
-    let size = Pos::new(
+fn mark_outside(coords: &HashSet<Pos3>) -> Matrix3<bool> {
+    let size = Pos3::new(
         coords.iter().map(|c| c.x).max().unwrap_or(0) + 2,
         coords.iter().map(|c| c.y).max().unwrap_or(0) + 2,
         coords.iter().map(|c| c.z).max().unwrap_or(0) + 2,
     );
-    let sides = all_sides();
     let mut outside = Matrix3::<bool>::new(size, false);
-    let mut to_visit: VecDeque<Pos> = VecDeque::new();
-    to_visit.push_back(Pos::new(0, 0, 0));
+    let mut to_visit: VecDeque<Pos3> = VecDeque::new();
+    to_visit.push_back(Pos3::new(0, 0, 0));
     while let Some(pos) = to_visit.pop_back() {
         if outside.get(pos) == Some(false) && !coords.contains(&pos) {
             outside.set(pos, true);
-            for &offset in sides.iter() {
+            for offset in Pos3::NEIGHBORS6 {
                 let new_pos = pos + offset;
                 if outside.get(new_pos) == Some(false) {
                     to_visit.push_back(new_pos)
@@ -145,35 +91,71 @@ fn mark_outside(coords: &HashSet<Pos>) -> Matrix3<bool> {
     outside
 }
 
-fn part2(input: Lines) -> String {
-    let coords = input
-        .flat_map(|line| line.parse::<Pos>().ok())
-        .collect::<HashSet<Pos>>();
+/// One layer of the droplet: lava, the water outside and air trapped inside.
+struct Slice<'a> {
+    coords: &'a HashSet<Pos3>,
+    outside: &'a Matrix3<bool>,
+    z: isize,
+}
+
+impl ToImage for Slice<'_> {
+    fn to_image(&self) -> Image {
+        let size = self.outside.size;
+        let mut image = Image::new(size.x as usize, size.y as usize, [0, 0, 0]);
+        for y in 0..size.y {
+            for x in 0..size.x {
+                let pos = Pos3::new(x, y, self.z);
+                let color = if self.coords.contains(&pos) {
+                    [220, 80, 30]
+                } else if self.outside.get(pos) == Some(true) {
+                    [40, 90, 200]
+                } else {
+                    [20, 20, 20]
+                };
+                image.set(Pos::new(x as usize, y as usize), color);
+            }
+        }
+        image.scale(8)
+    }
+}
+
+fn part2(input: Lines) -> Result<Answer> {
+    let coords = parse_coords(input)?;
 
     let outside = mark_outside(&coords);
-    let sides = all_sides();
+    for z in 0..outside.size.z {
+        snapshot(&Slice {
+            coords: &coords,
+            outside: &outside,
+            z,
+        });
+    }
 
-    coords
+    Ok(coords
         .iter()
         .copied()
         .map(|pos| {
-            sides
+            Pos3::NEIGHBORS6
                 .iter()
                 .copied()
                 .filter(|&s| outside.get(pos + s).unwrap_or(true))
                 .count()
         })
         .sum::<usize>()
-        .to_string()
+        .into())
 }
 
-fn main() {
+pub fn run_parts(runner: &Runner) {
     let input = include_str!("input.txt");
-    let runner: &Runner = create_runner!();
+    runner.known_answers(include_str!("answers.txt"));
     runner.run(named!(part1), input);
     runner.run(named!(part2), input);
 }
 
+fn main() {
+    run_parts(create_runner!());
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -182,7 +164,7 @@ mod tests {
     #[test]
     fn example() {
         let input = include_str!("example.txt");
-        verify!(part1, input, "64");
-        verify!(part2, input, "58");
+        verify!(part1, input, 64);
+        verify!(part2, input, 58);
     }
 }
@@ -1,5 +1,7 @@
-use advent_of_code::{create_runner, named, Named, Runner};
-use lazy_regex::regex_captures;
+use advent_of_code::{
+    captures, create_runner, named, par_map, parse_lines, Answer, LineError, Named, Result, Runner,
+};
+use lazy_regex::regex;
 use std::str::Lines;
 
 #[derive(PartialEq, Clone, Copy, Debug)]
@@ -55,7 +57,7 @@ impl std::ops::Add for Resources {
 
     fn add(self, rhs: Self) -> Self::Output {
         let mut amounts = self.amounts;
-        for (a, b) in amounts.iter_mut().zip(rhs.amounts.into_iter()) {
+        for (a, b) in amounts.iter_mut().zip(rhs.amounts) {
             *a += b
         }
         Self::Output { amounts }
@@ -99,7 +101,7 @@ impl std::ops::Sub for Resources {
 
     fn sub(self, rhs: Self) -> Self::Output {
         let mut amounts = self.amounts;
-        for (a, b) in amounts.iter_mut().zip(rhs.amounts.into_iter()) {
+        for (a, b) in amounts.iter_mut().zip(rhs.amounts) {
             *a -= b
         }
         Self::Output { amounts }
@@ -152,7 +154,7 @@ impl State {
                 } else if robots == 0 {
                     usize::MAX
                 } else {
-                    (cost - have + robots - 1) / robots
+                    (cost - have).div_ceil(robots)
                 }
             })
             .max()
@@ -198,35 +200,33 @@ struct Blueprint {
 }
 
 impl Blueprint {
-    fn parse(line: &str) -> Option<Self> {
-        let (_, id, ore_ore, clay_ore, obsidian_ore, obsidian_clay, geode_ore, geode_obsidian) = regex_captures!(
-            r"Blueprint (\d+): Each ore robot costs (\d+) ore. Each clay robot costs (\d+) ore. Each obsidian robot costs (\d+) ore and (\d+) clay. Each geode robot costs (\d+) ore and (\d+) obsidian.$",
-            line
-        )?;
+    fn parse(line: &str) -> Result<Self, LineError> {
+        let (id, ore_ore, clay_ore, obsidian_ore, obsidian_clay, geode_ore, geode_obsidian) =
+            captures::<(usize, usize, usize, usize, usize, usize, usize)>(
+                regex!(
+                    r"Blueprint (\d+): Each ore robot costs (\d+) ore. Each clay robot costs (\d+) ore. Each obsidian robot costs (\d+) ore and (\d+) clay. Each geode robot costs (\d+) ore and (\d+) obsidian.$"
+                ),
+                line,
+            )?;
         let robot_costs = vec![
             RobotCost {
                 collects: Resource::Ore,
-                cost: Resource::Ore * ore_ore.parse().ok()?,
+                cost: Resource::Ore * ore_ore,
             },
             RobotCost {
                 collects: Resource::Clay,
-                cost: Resource::Ore * clay_ore.parse().ok()?,
+                cost: Resource::Ore * clay_ore,
             },
             RobotCost {
                 collects: Resource::Obsidian,
-                cost: Resource::Ore * obsidian_ore.parse().ok()?
-                    + Resource::Clay * obsidian_clay.parse().ok()?,
+                cost: Resource::Ore * obsidian_ore + Resource::Clay * obsidian_clay,
             },
             RobotCost {
                 collects: Resource::Geode,
-                cost: Resource::Ore * geode_ore.parse().ok()?
-                    + Resource::Obsidian * geode_obsidian.parse().ok()?,
+                cost: Resource::Ore * geode_ore + Resource::Obsidian * geode_obsidian,
             },
         ];
-        Some(Self {
-            id: id.parse().ok()?,
-            robot_costs,
-        })
+        Ok(Self { id, robot_costs })
     }
     fn quality_level(&self) -> usize {
         self.id * self.geodes_opened(24)
@@ -254,30 +254,34 @@ impl Blueprint {
     }
 }
 
-fn part1(input: Lines) -> String {
-    input
-        .flat_map(Blueprint::parse)
-        .map(|b| b.quality_level())
+fn part1(input: Lines) -> Result<Answer> {
+    let blueprints = parse_lines(input, Blueprint::parse)?;
+    Ok(par_map(&blueprints, Blueprint::quality_level)
+        .into_iter()
         .sum::<usize>()
-        .to_string()
+        .into())
 }
 
-fn part2(input: Lines) -> String {
-    input
-        .flat_map(Blueprint::parse)
-        .take(3)
-        .map(|b| b.geodes_opened(32))
+fn part2(input: Lines) -> Result<Answer> {
+    let blueprints = parse_lines(input, Blueprint::parse)?;
+    let first = &blueprints[..blueprints.len().min(3)];
+    Ok(par_map(first, |b| b.geodes_opened(32))
+        .into_iter()
         .product::<usize>()
-        .to_string()
+        .into())
 }
 
-fn main() {
+pub fn run_parts(runner: &Runner) {
     let input = include_str!("input.txt");
-    let runner: &Runner = create_runner!();
+    runner.known_answers(include_str!("answers.txt"));
     runner.run(named!(part1), input);
     runner.run(named!(part2), input);
 }
 
+fn main() {
+    run_parts(create_runner!());
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -285,10 +289,8 @@ mod tests {
 
     #[test]
     fn parse_blueprint() {
-        let blueprints: Vec<Blueprint> = include_str!("example.txt")
-            .lines()
-            .flat_map(Blueprint::parse)
-            .collect();
+        let blueprints =
+            parse_lines(include_str!("example.txt").lines(), Blueprint::parse).unwrap();
         assert_eq!(blueprints.len(), 2);
         let expected = Blueprint {
             id: 1,
@@ -457,20 +459,16 @@ mod tests {
 
     #[test]
     fn geodes_opened_part1() {
-        let blueprints: Vec<Blueprint> = include_str!("example.txt")
-            .lines()
-            .flat_map(Blueprint::parse)
-            .collect();
+        let blueprints =
+            parse_lines(include_str!("example.txt").lines(), Blueprint::parse).unwrap();
         assert_eq!(blueprints[0].geodes_opened(24), 9);
         assert_eq!(blueprints[1].geodes_opened(24), 12);
     }
 
     #[test]
     fn geodes_opened_part2() {
-        let blueprints: Vec<Blueprint> = include_str!("example.txt")
-            .lines()
-            .flat_map(Blueprint::parse)
-            .collect();
+        let blueprints =
+            parse_lines(include_str!("example.txt").lines(), Blueprint::parse).unwrap();
         assert_eq!(blueprints[0].geodes_opened(32), 56);
         assert_eq!(blueprints[1].geodes_opened(32), 62);
     }
@@ -478,7 +476,7 @@ mod tests {
     #[test]
     fn example() {
         let input = include_str!("example.txt");
-        verify!(part1, input, "33");
-        verify!(part2, input, "3472");
+        verify!(part1, input, 33);
+        verify!(part2, input, 3472);
     }
 }
@@ -1,67 +1,83 @@
-use advent_of_code::{create_runner, named, Named, Runner};
+use advent_of_code::{
+    create_runner, named, parse_lines, Answer, Error, LineError, Named, Result, Runner,
+};
 use itertools::Itertools;
-use std::str::Lines;
+use std::str::{FromStr, Lines};
 
-fn find<T: PartialEq>(vec: &[T], num: T) -> usize {
-    vec.iter().position(|x| *x == num).unwrap()
+fn parse_numbers<T: FromStr>(input: Lines) -> Result<Vec<T>> {
+    parse_lines(input, |line| {
+        line.parse::<T>()
+            .map_err(|_| LineError::new(format!("expected a number, got {line:?}")))
+    })
+}
+
+fn find<T: PartialEq>(vec: &[T], num: T) -> Option<usize> {
+    vec.iter().position(|x| *x == num)
 }
 
 fn mix(vec: &mut Vec<usize>, num: usize, off: isize) {
-    let old_pos = find::<usize>(vec, num);
+    let old_pos = find::<usize>(vec, num).expect("every number is mixed");
     vec.remove(old_pos);
-    let new_pos = (old_pos as isize + off).rem_euclid(vec.len() as isize) as usize;
+    // a lone number stays where it is
+    let len = vec.len().max(1) as isize;
+    let new_pos = ((old_pos as isize + off.rem_euclid(len)) % len) as usize;
     vec.insert(new_pos, num);
 }
 
-fn get<T: Copy>(numbers: &[T], mixed: &Vec<usize>, index: usize) -> T {
+fn get<T: Copy>(numbers: &[T], mixed: &[usize], index: usize) -> T {
     numbers[mixed[index % mixed.len()]]
 }
 
-fn part1(input: Lines) -> String {
-    let numbers = input
-        .flat_map(|line| line.parse::<i16>().ok())
-        .collect_vec();
+/// The sum of the numbers 1000, 2000 and 3000 after the 0 once mixed.
+fn grove_coordinates(numbers: &[isize], mixed: &[usize]) -> Result<isize> {
+    let zero =
+        find(numbers, 0).ok_or_else(|| Error::InvalidInput("the file has no 0".to_string()))?;
+    let zero = find(mixed, zero).expect("every number is mixed");
+    [1000, 2000, 3000]
+        .map(|pos| get(numbers, mixed, zero + pos))
+        .into_iter()
+        .try_fold(0isize, isize::checked_add)
+        .ok_or_else(|| Error::InvalidInput("the grove coordinates overflow".to_string()))
+}
+
+fn part1(input: Lines) -> Result<Answer> {
+    let numbers = parse_numbers::<isize>(input)?;
     let mut mixed = (0..numbers.len()).collect_vec();
     for (id, &num) in numbers.iter().enumerate() {
-        mix(&mut mixed, id, num as isize)
+        mix(&mut mixed, id, num)
     }
-    let zero = find(&numbers, 0);
-    let zero = find(&mixed, zero);
-    [1000, 2000, 3000]
-        .map(|pos| get(&numbers, &mixed, zero + pos))
-        .iter()
-        .sum::<i16>()
-        .to_string()
+    Ok(grove_coordinates(&numbers, &mixed)?.into())
 }
 
-fn part2(input: Lines) -> String {
+fn part2(input: Lines) -> Result<Answer> {
     let decryption_key = 811589153;
-    let numbers = input
-        .flat_map(|line| line.parse::<isize>().ok())
-        .map(|n| n * decryption_key)
-        .collect_vec();
+    let numbers = parse_numbers::<isize>(input)?
+        .into_iter()
+        .map(|n| {
+            n.checked_mul(decryption_key)
+                .ok_or_else(|| Error::InvalidInput(format!("{n} is too large to decrypt")))
+        })
+        .collect::<Result<Vec<_>>>()?;
     let mut mixed = (0..numbers.len()).collect_vec();
     for _ in 0..10 {
         for (id, &num) in numbers.iter().enumerate() {
-            mix(&mut mixed, id, num as isize)
+            mix(&mut mixed, id, num)
         }
     }
-    let zero = find(&numbers, 0);
-    let zero = find(&mixed, zero);
-    [1000, 2000, 3000]
-        .map(|pos| get(&numbers, &mixed, zero + pos))
-        .iter()
-        .sum::<isize>()
-        .to_string()
+    Ok(grove_coordinates(&numbers, &mixed)?.into())
 }
 
-fn main() {
+pub fn run_parts(runner: &Runner) {
     let input = include_str!("input.txt");
-    let runner: &Runner = create_runner!();
+    runner.known_answers(include_str!("answers.txt"));
     runner.run(named!(part1), input);
     runner.run(named!(part2), input);
 }
 
+fn main() {
+    run_parts(create_runner!());
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -94,7 +110,21 @@ mod tests {
     #[test]
     fn example() {
         let input = include_str!("example.txt");
-        verify!(part1, input, "3");
-        verify!(part2, input, "1623178306");
+        verify!(part1, input, 3);
+        verify!(part2, input, 1623178306);
+    }
+
+    #[test]
+    fn errors() {
+        let no_zero = Err(Error::InvalidInput("the file has no 0".to_string()));
+        assert_eq!(part1("1\n2".lines()), no_zero);
+        assert_eq!(part2("".lines()), no_zero);
+        assert_eq!(part1("0".lines()), Ok(Answer::from(0)));
+        assert_eq!(
+            part2("0\n99999999999999".lines()),
+            Err(Error::InvalidInput(
+                "99999999999999 is too large to decrypt".to_string()
+            ))
+        );
     }
 }
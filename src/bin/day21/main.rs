@@ -1,5 +1,11 @@
-use advent_of_code::{create_runner, named, Named, Runner};
-use std::{collections::HashMap, str::Lines};
+use advent_of_code::{
+    captures, create_runner, named, parse_lines, Answer, Error, LineError, Named, Result, Runner,
+};
+use lazy_regex::regex;
+use std::{
+    collections::HashMap,
+    str::{FromStr, Lines},
+};
 
 #[derive(Clone, Copy)]
 enum Operation {
@@ -9,14 +15,16 @@ enum Operation {
     Divide,
 }
 
-impl Operation {
-    fn parse(text: &str) -> Option<Self> {
+impl FromStr for Operation {
+    type Err = ();
+
+    fn from_str(text: &str) -> Result<Self, ()> {
         match text {
-            "+" => Some(Operation::Add),
-            "-" => Some(Operation::Subtract),
-            "*" => Some(Operation::Multiply),
-            "/" => Some(Operation::Divide),
-            _ => None,
+            "+" => Ok(Operation::Add),
+            "-" => Ok(Operation::Subtract),
+            "*" => Ok(Operation::Multiply),
+            "/" => Ok(Operation::Divide),
+            _ => Err(()),
         }
     }
 }
@@ -26,139 +34,211 @@ enum Job {
     Expression { a: String, b: String, op: Operation },
 }
 
-impl Job {
-    fn parse(text: &str) -> Option<Self> {
-        text.parse::<i64>().ok().map(Job::Number).or_else(|| {
-            let parts: Vec<&str> = text.split_whitespace().collect();
-            Operation::parse(parts[1]).map(|op| Job::Expression {
-                a: parts[0].to_owned(),
-                b: parts[2].to_owned(),
-                op,
-            })
-        })
+fn parse_monkey(line: &str) -> Result<(String, Job), LineError> {
+    if regex!(r"^[a-z]+: -?\d+$").is_match(line) {
+        let (name, n) = captures::<(String, i64)>(regex!(r"^([a-z]+): (-?\d+)$"), line)?;
+        return Ok((name, Job::Number(n)));
+    }
+    let (name, a, op, b) = captures::<(String, String, Operation, String)>(
+        regex!(r"^([a-z]+): ([a-z]+) ([-+*/]) ([a-z]+)$"),
+        line,
+    )?;
+    Ok((name, Job::Expression { a, b, op }))
+}
+
+type Monkeys = HashMap<String, Job>;
+
+/// The monkeys by name. Every monkey waited for must be listed, once, and
+/// no monkey may end up waiting for itself.
+fn parse_monkeys(input: Lines) -> Result<Monkeys> {
+    let listed = parse_lines(input, parse_monkey)?;
+    let names = listed
+        .iter()
+        .map(|(name, _)| name.clone())
+        .collect::<Vec<_>>();
+    let mut monkeys = Monkeys::new();
+    for (line, (name, job)) in (1..).zip(listed) {
+        if monkeys.insert(name.clone(), job).is_some() {
+            return Err(Error::parse(line, format!("monkey {name} is listed twice")));
+        }
+    }
+    // checked in the order listed, so that the same problem is reported
+    let mut done = HashMap::new();
+    for name in &names {
+        check_waits(&monkeys, name, &mut done)?;
+    }
+    Ok(monkeys)
+}
+
+/// Checks that the monkeys `name` waits for are listed and never wait for
+/// `name` in turn. `done` records whether each monkey seen has been checked
+/// or is still being checked.
+fn check_waits<'a>(
+    monkeys: &'a Monkeys,
+    name: &'a str,
+    done: &mut HashMap<&'a str, bool>,
+) -> Result<()> {
+    match done.get(name) {
+        Some(true) => return Ok(()),
+        Some(false) => {
+            return Err(Error::InvalidInput(format!(
+                "monkey {name} ends up waiting for itself"
+            )))
+        }
+        None => {}
+    }
+    done.insert(name, false);
+    if let Job::Expression { a, b, .. } = &monkeys[name] {
+        for other in [a, b] {
+            if !monkeys.contains_key(other) {
+                return Err(Error::InvalidInput(format!(
+                    "monkey {name} waits for {other}, who isn't listed"
+                )));
+            }
+            check_waits(monkeys, other, done)?;
+        }
     }
+    done.insert(name, true);
+    Ok(())
 }
 
-fn parse_monkeys(input: Lines) -> HashMap<String, Job> {
-    input
-        .map(|line| {
-            let (name, job) = line.split_once(": ").unwrap();
-            let job = Job::parse(job).unwrap();
-            (name.to_owned(), job)
-        })
-        .collect()
+fn job<'a>(monkeys: &'a Monkeys, name: &str) -> Result<&'a Job> {
+    monkeys
+        .get(name)
+        .ok_or_else(|| Error::InvalidInput(format!("there is no monkey named {name}")))
 }
 
-fn math_op(op: Operation, a: i64, b: i64) -> i64 {
+fn math_op(op: Operation, a: i64, b: i64) -> Option<i64> {
     match op {
-        Operation::Add => a + b,
-        Operation::Subtract => a - b,
-        Operation::Multiply => a * b,
-        Operation::Divide => a / b,
+        Operation::Add => a.checked_add(b),
+        Operation::Subtract => a.checked_sub(b),
+        Operation::Multiply => a.checked_mul(b),
+        Operation::Divide => a.checked_div(b),
     }
 }
 
-fn yell(monkeys: &HashMap<String, Job>, monkey: &str) -> i64 {
-    let job = &monkeys[monkey];
-    match job {
-        Job::Number(n) => *n,
-        Job::Expression { a, b, op } => math_op(*op, yell(monkeys, a), yell(monkeys, b)),
+fn overflow(monkey: &str) -> Error {
+    Error::NoSolution(format!("monkey {monkey} overflows or divides by zero"))
+}
+
+fn yell(monkeys: &Monkeys, monkey: &str) -> Result<i64> {
+    match job(monkeys, monkey)? {
+        Job::Number(n) => Ok(*n),
+        Job::Expression { a, b, op } => {
+            let (a, b) = (yell(monkeys, a)?, yell(monkeys, b)?);
+            math_op(*op, a, b).ok_or_else(|| overflow(monkey))
+        }
     }
 }
 
-fn part1(input: Lines) -> String {
-    let monkeys = parse_monkeys(input);
-    yell(&monkeys, "root").to_string()
+fn part1(input: Lines) -> Result<Answer> {
+    let monkeys = parse_monkeys(input)?;
+    Ok(yell(&monkeys, "root")?.into())
 }
 
 fn is_human(name: &str) -> bool {
     name == "humn"
 }
 
-fn maybe_yell(monkeys: &HashMap<String, Job>, monkey: &str) -> Option<i64> {
+/// What `monkey` yells, or `None` when that depends on the human.
+fn maybe_yell(monkeys: &Monkeys, monkey: &str) -> Result<Option<i64>> {
     if is_human(monkey) {
-        None
-    } else {
-        let job = &monkeys[monkey];
-        match job {
-            Job::Number(n) => Some(*n),
-            Job::Expression { a, b, op } => maybe_yell(monkeys, a)
-                .zip(maybe_yell(monkeys, b))
-                .map(|(a, b)| math_op(*op, a, b)),
-        }
+        return Ok(None);
     }
+    match job(monkeys, monkey)? {
+        Job::Number(n) => Ok(Some(*n)),
+        Job::Expression { a, b, op } => match (maybe_yell(monkeys, a)?, maybe_yell(monkeys, b)?) {
+            (Some(a), Some(b)) => math_op(*op, a, b).map(Some).ok_or_else(|| overflow(monkey)),
+            _ => Ok(None),
+        },
+    }
+}
+
+/// `x / d`, when it divides evenly.
+fn exact_div(x: i64, d: i64) -> Option<i64> {
+    (x.checked_rem(d)? == 0).then(|| x / d)
 }
 
-fn solve(monkeys: &HashMap<String, Job>, monkey: &str, want: i64) -> i64 {
+/// What the human must yell for `monkey`, which depends on the human, to
+/// yell `want`.
+fn solve(monkeys: &Monkeys, monkey: &str, want: i64) -> Result<i64> {
     if is_human(monkey) {
-        want
-    } else {
-        let job = &monkeys[monkey];
-        match job {
-            Job::Number(_) => panic!("impossible to reach a number"),
-            Job::Expression {
-                a: a_name,
-                b: b_name,
-                op,
-            } => {
-                let a = maybe_yell(monkeys, a_name);
-                let b = maybe_yell(monkeys, b_name);
-                if let Some(a) = a {
-                    solve(
-                        monkeys,
-                        b_name,
-                        match op {
-                            Operation::Add => want - a,      // a + b = want
-                            Operation::Subtract => a - want, // a - b = want
-                            Operation::Multiply => want / a, // a * b = want
-                            Operation::Divide => a / want,   // a / b = want
-                        },
-                    )
-                } else if let Some(b) = b {
-                    solve(
-                        monkeys,
-                        a_name,
-                        match op {
-                            Operation::Add => want - b,      // a + b = want
-                            Operation::Subtract => want + b, // a - b = want
-                            Operation::Multiply => want / b, // a * b = want
-                            Operation::Divide => want * b,   // a / b = want
-                        },
-                    )
-                } else {
-                    panic!("both sides can't be human")
-                }
-            }
+        return Ok(want);
+    }
+    let Job::Expression {
+        a: a_name,
+        b: b_name,
+        op,
+    } = job(monkeys, monkey)?
+    else {
+        return Err(Error::NoSolution(format!(
+            "monkey {monkey} yells a number, whatever the human yells"
+        )));
+    };
+    let unsolvable = || {
+        Error::NoSolution(format!(
+            "no number from the human makes monkey {monkey} yell {want}"
+        ))
+    };
+    match (maybe_yell(monkeys, a_name)?, maybe_yell(monkeys, b_name)?) {
+        (Some(a), None) => {
+            let b = match op {
+                Operation::Add => want.checked_sub(a),      // a + b = want
+                Operation::Subtract => a.checked_sub(want), // a - b = want
+                Operation::Multiply => exact_div(want, a),  // a * b = want
+                Operation::Divide => a.checked_div(want),   // a / b = want
+            };
+            solve(monkeys, b_name, b.ok_or_else(unsolvable)?)
         }
+        (None, Some(b)) => {
+            let a = match op {
+                Operation::Add => want.checked_sub(b),      // a + b = want
+                Operation::Subtract => want.checked_add(b), // a - b = want
+                Operation::Multiply => exact_div(want, b),  // a * b = want
+                Operation::Divide => want.checked_mul(b),   // a / b = want
+            };
+            solve(monkeys, a_name, a.ok_or_else(unsolvable)?)
+        }
+        (None, None) => Err(Error::NoSolution(format!(
+            "both monkeys {monkey} waits for depend on the human"
+        ))),
+        (Some(_), Some(_)) => Err(Error::NoSolution(format!(
+            "monkey {monkey} doesn't depend on the human"
+        ))),
     }
 }
 
-fn part2(input: Lines) -> String {
-    let monkeys = parse_monkeys(input);
-    let (a_name, b_name) = match &monkeys["root"] {
-        Job::Expression { a, b, op: _ } => Some((a, b)),
-        _ => None,
-    }
-    .expect("root monkey must be an expression");
-    let a = maybe_yell(&monkeys, a_name);
-    let b = maybe_yell(&monkeys, b_name);
-    if let Some(a) = a {
-        solve(&monkeys, b_name, a)
-    } else if let Some(b) = b {
-        solve(&monkeys, a_name, b)
-    } else {
-        panic!()
-    }
-    .to_string()
+fn part2(input: Lines) -> Result<Answer> {
+    let monkeys = parse_monkeys(input)?;
+    let Job::Expression { a, b, .. } = job(&monkeys, "root")? else {
+        return Err(Error::InvalidInput(
+            "the root monkey must wait for two others".to_string(),
+        ));
+    };
+    // root checks that both sides are equal
+    let human = match (maybe_yell(&monkeys, a)?, maybe_yell(&monkeys, b)?) {
+        (Some(a), None) => solve(&monkeys, b, a)?,
+        (None, Some(b)) => solve(&monkeys, a, b)?,
+        _ => {
+            return Err(Error::NoSolution(
+                "exactly one side of root must depend on the human".to_string(),
+            ))
+        }
+    };
+    Ok(human.into())
 }
 
-fn main() {
+pub fn run_parts(runner: &Runner) {
     let input = include_str!("input.txt");
-    let runner: &Runner = create_runner!();
+    runner.known_answers(include_str!("answers.txt"));
     runner.run(named!(part1), input);
     runner.run(named!(part2), input);
 }
 
+fn main() {
+    run_parts(create_runner!());
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -167,7 +247,64 @@ mod tests {
     #[test]
     fn example() {
         let input = include_str!("example.txt");
-        verify!(part1, input, "152");
-        verify!(part2, input, "301");
+        verify!(part1, input, 152);
+        verify!(part2, input, 301);
+    }
+
+    #[test]
+    fn errors() {
+        let input = include_str!("example.txt");
+        assert_eq!(
+            part1("root: pppw + sjmn\nsjmn: 5 % 2".lines()),
+            Err(Error::parse(
+                2,
+                "expected `^([a-z]+): ([a-z]+) ([-+*/]) ([a-z]+)$`, got \"sjmn: 5 % 2\""
+            ))
+        );
+        assert_eq!(
+            part1("root: 1\nroot: 2".lines()),
+            Err(Error::parse(2, "monkey root is listed twice"))
+        );
+        assert_eq!(
+            part1("root: aaaa + bbbb\naaaa: 1".lines()),
+            Err(Error::InvalidInput(
+                "monkey root waits for bbbb, who isn't listed".to_string()
+            ))
+        );
+        assert_eq!(
+            part1("root: aaaa + bbbb\naaaa: 1\nbbbb: root * aaaa".lines()),
+            Err(Error::InvalidInput(
+                "monkey root ends up waiting for itself".to_string()
+            ))
+        );
+        assert_eq!(
+            part1("root: aaaa / bbbb\naaaa: 1\nbbbb: 0".lines()),
+            Err(Error::NoSolution(
+                "monkey root overflows or divides by zero".to_string()
+            ))
+        );
+        let no_root = input.replace("root", "toor");
+        assert_eq!(
+            part2(no_root.lines()),
+            Err(Error::InvalidInput(
+                "there is no monkey named root".to_string()
+            ))
+        );
+        assert_eq!(
+            part2(
+                "root: aaaa + humn\naaaa: 3\nhumn: 5"
+                    .replace("humn", "huma")
+                    .lines()
+            ),
+            Err(Error::NoSolution(
+                "exactly one side of root must depend on the human".to_string()
+            ))
+        );
+        assert_eq!(
+            part2("root: aaaa + bbbb\naaaa: 3\nbbbb: humn * cccc\ncccc: 2\nhumn: 5".lines()),
+            Err(Error::NoSolution(
+                "no number from the human makes monkey bbbb yell 3".to_string()
+            ))
+        );
     }
 }
@@ -1,4 +1,7 @@
-use advent_of_code::{create_runner, named, Named, Runner};
+use advent_of_code::{
+    create_runner, named, sections, trace, Answer, Error, Grid, LineError, Named, Pos, Render,
+    Result, Runner, Section,
+};
 use itertools::Itertools;
 use std::{collections::HashMap, str::Lines};
 
@@ -8,31 +11,55 @@ struct Cube {
 }
 
 impl Cube {
-    fn new(lines: Vec<&str>) -> Self {
-        let size = lines[0].parse::<isize>().unwrap();
-        let rules = lines[1..]
-            .iter()
-            .filter(|line| !line.starts_with("//"))
-            .flat_map(|line| {
-                let (a, b) = Self::parse_rules(line);
-                [(a, b), (Self::flip_rule(&b), Self::flip_rule(&a))]
-            })
+    /// `section` holds the cube size followed by one rule per line.
+    fn new(section: &Section) -> Result<Self> {
+        let (size, rules) = section
+            .lines
+            .split_first()
+            .ok_or_else(|| Error::parse(section.first_line, "expected cube size"))?;
+        let size = size
+            .parse::<isize>()
+            .ok()
+            .filter(|&size| size > 0)
+            .ok_or_else(|| Error::parse(section.first_line, "expected cube size"))?;
+        let rules = Section {
+            first_line: section.first_line + 1,
+            lines: rules.to_vec(),
+        };
+        let rules = rules
+            .parse_lines(|line| {
+                if line.starts_with("//") {
+                    Ok(None)
+                } else {
+                    Self::parse_rules(line).map(Some)
+                }
+            })?
+            .into_iter()
+            .flatten()
+            .flatten()
             .collect();
-        Self { size, rules }
+        Ok(Self { size, rules })
     }
 
-    fn parse_rules(line: &str) -> (State, State) {
-        let (a, b) = line.split_once(" -> ").unwrap();
-        (Self::parse_rule(a), Self::parse_rule(b))
+    fn parse_rules(line: &str) -> Result<[(State, State); 2], LineError> {
+        let (a, b) = line
+            .split_once(" -> ")
+            .ok_or_else(|| LineError::new("expected rule `a -> b`"))?;
+        let a = Self::parse_rule(a)?;
+        let b = Self::parse_rule(b)?;
+        Ok([(a, b), (Self::flip_rule(&b), Self::flip_rule(&a))])
     }
 
-    fn parse_rule(rule: &str) -> State {
-        let fields = rule.split_whitespace().collect_vec();
-        State {
-            row: fields[0].parse::<isize>().unwrap(),
-            col: fields[1].parse::<isize>().unwrap(),
-            dir: Facing::new(fields[2]).unwrap(),
-        }
+    fn parse_rule(rule: &str) -> Result<State, LineError> {
+        let error = || LineError::new(format!("expected `row col dir`, got {rule:?}"));
+        let (row, col, dir) = rule.split_whitespace().collect_tuple().ok_or_else(error)?;
+        Ok(State {
+            pos: Pos::new(
+                col.parse::<isize>().map_err(|_| error())?,
+                row.parse::<isize>().map_err(|_| error())?,
+            ),
+            dir: Facing::new(dir).ok_or_else(error)?,
+        })
     }
 
     fn flip_rule(rule: &State) -> State {
@@ -103,121 +130,168 @@ impl Facing {
             Facing::Up => Facing::Down,
         }
     }
-}
 
-fn wrap_flat(map: &Map, state: &State) -> State {
-    match state.dir {
-        Facing::Right => State {
-            col: (0..state.col)
-                .find(|&c| map.get(state.row, c).is_some())
-                .unwrap() as isize,
-            ..*state
-        },
-        Facing::Down => State {
-            row: (0..state.row)
-                .find(|&r| map.get(r, state.col).is_some())
-                .unwrap() as isize,
-            ..*state
-        },
-        Facing::Left => State {
-            col: (state.col..map.tiles[state.row as usize].len() as isize)
-                .rev()
-                .find(|&c| map.get(state.row, c).is_some())
-                .unwrap() as isize,
-            ..*state
-        },
-        Facing::Up => State {
-            row: (state.row..map.tiles.len() as isize)
-                .rev()
-                .find(|&r| map.get(r, state.col).is_some())
-                .unwrap() as isize,
-            ..*state
-        },
+    /// The letter which stands for the facing in cube rules.
+    fn letter(self) -> char {
+        match self {
+            Facing::Right => 'R',
+            Facing::Down => 'D',
+            Facing::Left => 'L',
+            Facing::Up => 'U',
+        }
+    }
+
+    fn offset(self) -> Pos {
+        match self {
+            Facing::Right => Pos::RIGHT,
+            Facing::Down => Pos::DOWN,
+            Facing::Left => Pos::LEFT,
+            Facing::Up => Pos::UP,
+        }
     }
+
+    fn arrow(self) -> char {
+        match self {
+            Facing::Right => '>',
+            Facing::Down => 'v',
+            Facing::Left => '<',
+            Facing::Up => '^',
+        }
+    }
+}
+
+/// Walks back against the facing to the opposite edge of the map.
+fn wrap_flat(map: &Map, state: &State) -> Result<State> {
+    let back = -state.dir.offset();
+    let pos = itertools::iterate(state.pos, |&pos| pos + back)
+        .take_while(|&pos| map.get(pos).is_some())
+        .last()
+        .unwrap_or(state.pos);
+    Ok(State { pos, ..*state })
 }
 
-fn wrap_cube(map: &Map, state: &State) -> State {
+/// Moves over the edge of a face onto the face which the cube rules join
+/// it to. A missing rule is an error in the input.
+fn wrap_cube(map: &Map, state: &State) -> Result<State> {
     let cube = &map.cube;
+    let Pos { x: col, y: row } = state.pos;
     let cube_state_old = State {
-        row: state.row / cube.size,
-        col: state.col / cube.size,
+        pos: Pos::new(col / cube.size, row / cube.size),
         dir: state.dir,
     };
     let edge_dist = match state.dir {
-        Facing::Right => state.row % cube.size,
-        Facing::Down => cube.size - (state.col % cube.size) - 1,
-        Facing::Left => cube.size - (state.row % cube.size) - 1,
-        Facing::Up => state.col % cube.size,
+        Facing::Right => row % cube.size,
+        Facing::Down => cube.size - (col % cube.size) - 1,
+        Facing::Left => cube.size - (row % cube.size) - 1,
+        Facing::Up => col % cube.size,
     };
-    let cube_state_new = cube
-        .rules
-        .get(&cube_state_old)
-        .unwrap_or_else(|| panic!("no rule for {cube_state_old:?}"));
+    let cube_state_new = cube.rules.get(&cube_state_old).ok_or_else(|| {
+        Error::InvalidInput(format!(
+            "no cube rule for leaving `{} {} {}`",
+            cube_state_old.pos.y,
+            cube_state_old.pos.x,
+            cube_state_old.dir.letter()
+        ))
+    })?;
     let (row, col) = match cube_state_new.dir {
         Facing::Right => (edge_dist, 0),
         Facing::Down => (0, cube.size - edge_dist - 1),
         Facing::Left => (cube.size - edge_dist - 1, cube.size - 1),
         Facing::Up => (cube.size - 1, edge_dist),
     };
-    State {
-        row: cube_state_new.row * cube.size + row,
-        col: cube_state_new.col * cube.size + col,
+    Ok(State {
+        pos: cube_state_new.pos * cube.size + Pos::new(col, row),
         dir: cube_state_new.dir,
-    }
+    })
 }
 
-type WrapFn = fn(map: &Map, state: &State) -> State;
+type WrapFn = fn(map: &Map, state: &State) -> Result<State>;
 
 struct Map {
-    tiles: Vec<Vec<Option<MapTile>>>,
+    tiles: Grid<Option<MapTile>>,
+    /// The leftmost open tile of the top row, facing right.
+    start: State,
     cube: Cube,
     wrap: WrapFn,
 }
 
 impl Map {
-    fn new(input: Vec<&str>, cube: Cube, wrap: WrapFn) -> Self {
-        let tiles = input
+    /// Rows may be ragged, missing tiles at the end are off the map.
+    fn new(input: Vec<&str>, cube: Cube, wrap: WrapFn) -> Result<Self> {
+        let width = input.iter().map(|row| row.len()).max().unwrap_or_default();
+        let rows = input
             .iter()
-            .map(|row| row.chars().map(MapTile::new).collect_vec())
+            .map(|row| format!("{row:width$}"))
             .collect_vec();
-        Map { tiles, cube, wrap }
+        let tiles = Grid::parse(rows.iter().map(String::as_str), |c| match c {
+            ' ' => Some(None),
+            _ => MapTile::new(c).map(Some),
+        })?;
+        let col = rows
+            .first()
+            .and_then(|row| row.find('.'))
+            .ok_or_else(|| Error::parse(1, "the top row has no open tile"))?;
+        Ok(Map {
+            tiles,
+            start: State::new(col as isize),
+            cube,
+            wrap,
+        })
     }
 
-    fn get(&self, row: isize, col: isize) -> Option<MapTile> {
-        if row >= 0 && col >= 0 {
-            self.tiles
-                .get(row as usize)
-                .and_then(|row| row.get(col as usize))
-                .and_then(|&x| x)
-        } else {
-            None
-        }
+    fn get(&self, pos: Pos) -> Option<MapTile> {
+        self.tiles.get(pos.cast()?).copied().flatten()
     }
 
     fn get_tile(&self, state: &State) -> Option<MapTile> {
-        self.get(state.row, state.col)
-    }
-
-    fn start(&self) -> State {
-        let col = self.tiles[0]
-            .iter()
-            .position(|c| c == &Some(MapTile::Open))
-            .unwrap() as isize;
-        State::new(col)
+        self.get(state.pos)
     }
 
-    fn forward(&self, state: &State) -> State {
+    fn forward(&self, state: &State) -> Result<State> {
         let mut next = state.forward();
         let next_tile = if let Some(tile) = self.get_tile(&next) {
             tile
         } else {
-            next = (self.wrap)(self, state);
-            self.get_tile(&next).unwrap()
+            next = (self.wrap)(self, state)?;
+            self.get_tile(&next).ok_or_else(|| {
+                Error::InvalidInput(format!(
+                    "wrapping from {} leads off the map to {}",
+                    state.pos, next.pos
+                ))
+            })?
         };
-        match next_tile {
+        Ok(match next_tile {
             MapTile::Open => next,
             MapTile::Solid => *state,
+        })
+    }
+}
+
+impl Render for Map {
+    fn render(&self) -> Vec<String> {
+        self.tiles.render(|tile| match tile {
+            None => ' ',
+            Some(MapTile::Open) => '.',
+            Some(MapTile::Solid) => '#',
+        })
+    }
+}
+
+/// The map with the current state drawn as an arrow.
+struct Position<'a> {
+    map: &'a Map,
+    state: State,
+}
+
+impl Render for Position<'_> {
+    fn render(&self) -> Vec<String> {
+        let mut rows = self.map.render();
+        if let Some(Pos { x, y }) = self.state.pos.cast::<usize>() {
+            if let Some(row) = rows.get_mut(y).filter(|row| x < row.len()) {
+                row.replace_range(x..x + 1, &self.state.dir.arrow().to_string());
+            }
         }
+        rows
     }
 }
 
@@ -244,70 +318,64 @@ enum PathItem {
 }
 
 impl PathItem {
-    fn new(input: &str) -> Self {
-        TurnDir::new(input)
-            .map(PathItem::Turn)
-            .unwrap_or_else(|| PathItem::Move(input.parse::<usize>().unwrap()))
+    /// A turn or a number of tiles to move, found at `column` of its line.
+    fn parse(text: &str, column: usize) -> Result<Self, LineError> {
+        if let Some(dir) = TurnDir::new(text) {
+            return Ok(PathItem::Turn(dir));
+        }
+        text.parse::<usize>().map(PathItem::Move).map_err(|_| {
+            LineError::at(
+                column,
+                format!("expected `L`, `R` or a number, got {text:?}"),
+            )
+        })
     }
 }
 
-fn parse_path(input: &str) -> Vec<PathItem> {
-    lazy_regex::regex!(r"R|L|([0-9]+)")
-        .find_iter(input)
-        .map(|m| PathItem::new(m.as_str()))
-        .collect_vec()
+fn parse_path(line: &str) -> Result<Vec<PathItem>, LineError> {
+    lazy_regex::regex!(r"[0-9]+|.")
+        .find_iter(line)
+        .map(|m| PathItem::parse(m.as_str(), m.start() + 1))
+        .collect()
 }
 
-fn parse_input(mut input: Lines, wrap: WrapFn) -> (Map, Vec<PathItem>) {
-    let cube = input
-        .by_ref()
-        .take_while(|line| !line.is_empty())
-        .collect_vec();
-    let cube = Cube::new(cube);
-    let map = input
-        .by_ref()
-        .take_while(|line| !line.is_empty())
-        .collect_vec();
-    let map = Map::new(map, cube, wrap);
-    let path = input.next().unwrap();
-    let path = parse_path(path);
-    (map, path)
+fn parse_input(input: Lines, wrap: WrapFn) -> Result<(Map, Vec<PathItem>)> {
+    let sections = sections(input);
+    let [cube, map, path] = &sections[..] else {
+        return Err(Error::InvalidInput(format!(
+            "expected the cube, the map and the path, got {} sections",
+            sections.len()
+        )));
+    };
+    let cube = Cube::new(cube)?;
+    let map = Map::new(map.lines.clone(), cube, wrap)
+        .map_err(|error| error.offset_lines(map.first_line - 1))?;
+    let path = path
+        .parse_lines(parse_path)?
+        .into_iter()
+        .flatten()
+        .collect();
+    Ok((map, path))
 }
 
 #[derive(PartialEq, Eq, Clone, Copy, Hash, Debug)]
 struct State {
-    row: isize,
-    col: isize,
+    pos: Pos,
     dir: Facing,
 }
 
 impl State {
     fn new(col: isize) -> Self {
         Self {
-            row: 0,
-            col,
+            pos: Pos::new(col, 0),
             dir: Facing::Right,
         }
     }
 
     fn forward(&self) -> Self {
-        match self.dir {
-            Facing::Right => Self {
-                col: self.col + 1,
-                ..*self
-            },
-            Facing::Down => Self {
-                row: self.row + 1,
-                ..*self
-            },
-            Facing::Left => Self {
-                col: self.col - 1,
-                ..*self
-            },
-            Facing::Up => Self {
-                row: self.row - 1,
-                ..*self
-            },
+        Self {
+            pos: self.pos + self.dir.offset(),
+            ..*self
         }
     }
 
@@ -318,43 +386,52 @@ impl State {
         }
     }
 
-    fn follow(&self, path: PathItem, map: &Map) -> Self {
+    fn follow(&self, path: PathItem, map: &Map) -> Result<Self> {
         match path {
-            PathItem::Move(n) => itertools::iterate(*self, |&s| map.forward(&s))
-                .nth(n)
-                .unwrap(),
-            PathItem::Turn(dir) => self.turn(dir),
+            PathItem::Move(n) => (0..n).try_fold(*self, |state, _| {
+                let state = map.forward(&state)?;
+                trace("forward", &Position { map, state });
+                Ok(state)
+            }),
+            PathItem::Turn(dir) => {
+                let state = self.turn(dir);
+                trace("turn", &Position { map, state });
+                Ok(state)
+            }
         }
     }
 
     fn password(&self) -> isize {
-        1000 * (self.row + 1) + 4 * (self.col + 1) + (self.dir as isize)
+        1000 * (self.pos.y + 1) + 4 * (self.pos.x + 1) + (self.dir as isize)
     }
 }
 
-fn part1(input: Lines) -> String {
-    let (map, path) = parse_input(input, wrap_flat);
-    let state = path
-        .into_iter()
-        .fold(map.start(), |state, path| state.follow(path, &map));
-    state.password().to_string()
+fn walk(map: &Map, path: Vec<PathItem>) -> Result<State> {
+    path.into_iter()
+        .try_fold(map.start, |state, item| state.follow(item, map))
 }
 
-fn part2(input: Lines) -> String {
-    let (map, path) = parse_input(input, wrap_cube);
-    let state = path
-        .into_iter()
-        .fold(map.start(), |state, path| state.follow(path, &map));
-    state.password().to_string()
+fn part1(input: Lines) -> Result<Answer> {
+    let (map, path) = parse_input(input, wrap_flat)?;
+    Ok(walk(&map, path)?.password().into())
 }
 
-fn main() {
+fn part2(input: Lines) -> Result<Answer> {
+    let (map, path) = parse_input(input, wrap_cube)?;
+    Ok(walk(&map, path)?.password().into())
+}
+
+pub fn run_parts(runner: &Runner) {
     let input = include_str!("input.txt");
-    let runner: &Runner = create_runner!();
+    runner.known_answers(include_str!("answers.txt"));
     runner.run(named!(part1), input);
     runner.run(named!(part2), input);
 }
 
+fn main() {
+    run_parts(create_runner!());
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -364,38 +441,79 @@ mod tests {
     fn test_parse_path() {
         assert_eq!(
             parse_path("5L7"),
-            vec![
+            Ok(vec![
                 PathItem::Move(5),
                 PathItem::Turn(TurnDir::Left),
                 PathItem::Move(7)
-            ]
+            ])
+        );
+        assert_eq!(
+            parse_path("5L7X"),
+            Err(LineError::at(4, "expected `L`, `R` or a number, got \"X\""))
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let input = include_str!("example.txt");
+        let path = input.lines().last().unwrap();
+        assert_eq!(
+            part1(input.replace(path, "10R99999999999999999999").lines()).err(),
+            Some(Error::parse_at(
+                input.lines().count(),
+                4,
+                "expected `L`, `R` or a number, got \"99999999999999999999\""
+            ))
+        );
+        let first_row = input.lines().find(|line| line.contains('.')).unwrap();
+        assert_eq!(
+            part2(
+                input
+                    .replacen(first_row, &first_row.replace('.', "#"), 1)
+                    .lines()
+            )
+            .err(),
+            Some(Error::parse(
+                input.lines().position(|line| line == first_row).unwrap() + 1,
+                "the top row has no open tile"
+            ))
         );
     }
 
     #[test]
     fn test_parse_input() {
         let input = include_str!("example.txt");
-        let (map, path) = parse_input(input.lines(), wrap_flat);
-        assert_ne!(map.tiles.len(), 0);
+        let (map, path) = parse_input(input.lines(), wrap_flat).unwrap();
+        assert_ne!(map.tiles.height(), 0);
         assert_ne!(path.len(), 0);
     }
 
+    #[test]
+    fn test_render_position() {
+        let input = include_str!("example.txt");
+        let (map, _) = parse_input(input.lines(), wrap_flat).unwrap();
+        let state = map.start.turn(TurnDir::Right);
+        let rows = Position { map: &map, state }.render();
+        assert_eq!(rows.len(), 12);
+        assert_eq!(rows[0], "        v..#    ");
+        assert_eq!(rows[1], map.render()[1]);
+    }
+
     #[test]
     fn test_wrap_flat() {
         let input = include_str!("example.txt");
-        let (map, _) = parse_input(input.lines(), wrap_flat);
+        let (map, _) = parse_input(input.lines(), wrap_flat).unwrap();
         assert_eq!(
             wrap_flat(
                 &map,
                 &State {
-                    row: 0,
-                    col: 8,
+                    pos: Pos::new(8, 0),
                     dir: Facing::Left
                 }
-            ),
+            )
+            .unwrap(),
             State {
-                row: 0,
-                col: 11,
+                pos: Pos::new(11, 0),
                 dir: Facing::Left
             }
         );
@@ -403,14 +521,13 @@ mod tests {
             wrap_flat(
                 &map,
                 &State {
-                    row: 4,
-                    col: 0,
+                    pos: Pos::new(0, 4),
                     dir: Facing::Left
                 }
-            ),
+            )
+            .unwrap(),
             State {
-                row: 4,
-                col: 11,
+                pos: Pos::new(11, 4),
                 dir: Facing::Left
             }
         );
@@ -419,19 +536,18 @@ mod tests {
     #[test]
     fn test_wrap_cube() {
         let input = include_str!("example.txt");
-        let (map, _) = parse_input(input.lines(), wrap_cube);
+        let (map, _) = parse_input(input.lines(), wrap_cube).unwrap();
         assert_eq!(
             wrap_cube(
                 &map,
                 &State {
-                    row: 0,
-                    col: 8,
+                    pos: Pos::new(8, 0),
                     dir: Facing::Left
                 }
-            ),
+            )
+            .unwrap(),
             State {
-                row: 4,
-                col: 4,
+                pos: Pos::new(4, 4),
                 dir: Facing::Down
             }
         );
@@ -439,33 +555,58 @@ mod tests {
             wrap_cube(
                 &map,
                 &State {
-                    row: 4,
-                    col: 0,
+                    pos: Pos::new(0, 4),
                     dir: Facing::Left
                 }
-            ),
+            )
+            .unwrap(),
             State {
-                row: 11,
-                col: 15,
+                pos: Pos::new(15, 11),
                 dir: Facing::Up
             }
         );
     }
 
+    #[test]
+    fn test_missing_cube_rule() {
+        let input = include_str!("example.txt").replace("1 1 D -> 2 2 R\n", "");
+        let (map, _) = parse_input(input.lines(), wrap_cube).unwrap();
+        assert_eq!(
+            wrap_cube(
+                &map,
+                &State {
+                    pos: Pos::new(5, 7),
+                    dir: Facing::Down
+                }
+            )
+            .err(),
+            Some(Error::InvalidInput(
+                "no cube rule for leaving `1 1 D`".into()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_rule_error() {
+        assert_eq!(
+            Cube::new(&sections(["4", "// comment", "0 2 L -> 1 1"])[0]).err(),
+            Some(Error::parse(3, "expected `row col dir`, got \"1 1\""))
+        );
+    }
+
     #[test]
     fn test_follow() {
         let input = include_str!("example.txt");
-        let (map, _) = parse_input(input.lines(), wrap_flat);
+        let (map, _) = parse_input(input.lines(), wrap_flat).unwrap();
         assert_eq!(
             State {
-                row: 4,
-                col: 0,
+                pos: Pos::new(0, 4),
                 dir: Facing::Left
             }
-            .follow(PathItem::Move(1), &map),
+            .follow(PathItem::Move(1), &map)
+            .unwrap(),
             State {
-                row: 4,
-                col: 0,
+                pos: Pos::new(0, 4),
                 dir: Facing::Left
             }
         );
@@ -474,15 +615,12 @@ mod tests {
     #[test]
     fn test_example_state_part1() {
         let input = include_str!("example.txt");
-        let (map, path) = parse_input(input.lines(), wrap_flat);
-        let state = path
-            .into_iter()
-            .fold(map.start(), |state, path| state.follow(path, &map));
+        let (map, path) = parse_input(input.lines(), wrap_flat).unwrap();
+        let state = walk(&map, path).unwrap();
         assert_eq!(
             state,
             State {
-                row: 5,
-                col: 7,
+                pos: Pos::new(7, 5),
                 dir: Facing::Right
             }
         );
@@ -491,7 +629,7 @@ mod tests {
     #[test]
     fn example() {
         let input = include_str!("example.txt");
-        verify!(part1, input, "6032");
-        verify!(part2, input, "5031");
+        verify!(part1, input, 6032);
+        verify!(part2, input, 5031);
     }
 }
@@ -0,0 +1,183 @@
+use advent_of_code::{create_runner, named, Answer, Grid, Named, Pos, Result, Runner};
+use itertools::Itertools;
+use std::{
+    collections::{HashMap, HashSet},
+    str::Lines,
+};
+
+#[derive(Clone, Copy, Debug)]
+enum Direction {
+    North,
+    South,
+    West,
+    East,
+}
+
+impl Direction {
+    const ORDER: [Direction; 4] = [
+        Direction::North,
+        Direction::South,
+        Direction::West,
+        Direction::East,
+    ];
+
+    /// The offsets which must be free of elves, the first is the move itself.
+    fn checks(self) -> [Pos; 3] {
+        let [a, b, c] = match self {
+            Direction::North => [(0, -1), (1, -1), (-1, -1)],
+            Direction::South => [(0, 1), (1, 1), (-1, 1)],
+            Direction::West => [(-1, 0), (-1, -1), (-1, 1)],
+            Direction::East => [(1, 0), (1, -1), (1, 1)],
+        };
+        [a, b, c].map(|(x, y)| Pos::new(x, y))
+    }
+}
+
+struct Grove {
+    elves: HashSet<Pos>,
+    first_direction: usize,
+}
+
+impl Grove {
+    fn parse(input: Lines) -> Result<Self> {
+        let grid = Grid::parse(input, |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        })?;
+        let elves = grid
+            .iter()
+            .filter(|(_, &elf)| elf)
+            .map(|(pos, _)| Pos::new(pos.x as isize, pos.y as isize))
+            .collect();
+        Ok(Self {
+            elves,
+            first_direction: 0,
+        })
+    }
+
+    fn is_free(&self, pos: &Pos, offsets: &[Pos]) -> bool {
+        offsets
+            .iter()
+            .all(|&offset| !self.elves.contains(&(*pos + offset)))
+    }
+
+    fn propose(&self, elf: &Pos) -> Option<Pos> {
+        if self.is_free(elf, &Pos::NEIGHBORS8) {
+            return None;
+        }
+        Direction::ORDER
+            .iter()
+            .cycle()
+            .skip(self.first_direction)
+            .take(Direction::ORDER.len())
+            .map(|direction| direction.checks())
+            .find(|checks| self.is_free(elf, checks))
+            .map(|checks| *elf + checks[0])
+    }
+
+    /// Returns whether any elf moved.
+    fn round(&mut self) -> bool {
+        let proposals = self
+            .elves
+            .iter()
+            .flat_map(|elf| self.propose(elf).map(|to| (*elf, to)))
+            .collect_vec();
+        let counts = proposals.iter().counts_by(|(_, to)| *to);
+        let moves = proposals
+            .into_iter()
+            .filter(|(_, to)| counts[to] == 1)
+            .collect::<HashMap<_, _>>();
+        self.elves = self
+            .elves
+            .iter()
+            .map(|elf| moves.get(elf).copied().unwrap_or(*elf))
+            .collect();
+        self.first_direction = (self.first_direction + 1) % Direction::ORDER.len();
+        !moves.is_empty()
+    }
+
+    /// Empty ground tiles in the smallest rectangle containing every elf.
+    fn empty_ground(&self) -> usize {
+        if self.elves.is_empty() {
+            return 0;
+        }
+        let (min_x, max_x) = self
+            .elves
+            .iter()
+            .map(|e| e.x)
+            .minmax()
+            .into_option()
+            .unwrap();
+        let (min_y, max_y) = self
+            .elves
+            .iter()
+            .map(|e| e.y)
+            .minmax()
+            .into_option()
+            .unwrap();
+        let area = (max_x - min_x + 1) * (max_y - min_y + 1);
+        area as usize - self.elves.len()
+    }
+}
+
+fn part1(input: Lines) -> Result<Answer> {
+    let mut grove = Grove::parse(input)?;
+    for _ in 0..10 {
+        grove.round();
+    }
+    Ok(grove.empty_ground().into())
+}
+
+fn part2(input: Lines) -> Result<Answer> {
+    let mut grove = Grove::parse(input)?;
+    let rounds = (1..).find(|_| !grove.round()).unwrap();
+    Ok(rounds.into())
+}
+
+pub fn run_parts(runner: &Runner) {
+    let input = include_str!("input.txt");
+    runner.known_answers(include_str!("answers.txt"));
+    runner.run(named!(part1), input);
+    runner.run(named!(part2), input);
+}
+
+fn main() {
+    run_parts(create_runner!());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use advent_of_code::{verify, Error};
+
+    #[test]
+    fn test_small_example() {
+        let input = ".....\n..##.\n..#..\n.....\n..##.\n.....";
+        let mut grove = Grove::parse(input.lines()).unwrap();
+        for _ in 0..3 {
+            assert!(grove.round());
+        }
+        let expected = [(2, 0), (4, 1), (0, 2), (4, 3), (2, 5)]
+            .map(|(x, y)| Pos::new(x, y))
+            .into_iter()
+            .collect::<HashSet<_>>();
+        assert_eq!(grove.elves, expected);
+        assert!(!grove.round());
+    }
+
+    #[test]
+    fn example() {
+        let input = include_str!("example.txt");
+        verify!(part1, input, 110);
+        verify!(part2, input, 20);
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            Grove::parse("..#\n.x.".lines()).err(),
+            Some(Error::parse_at(2, 2, "unexpected tile 'x'"))
+        );
+    }
+}
@@ -0,0 +1,204 @@
+use advent_of_code::{create_runner, named, Answer, Error, Named, Pos, Result, Runner};
+use itertools::Itertools;
+use std::{collections::HashSet, str::Lines};
+
+#[derive(Clone, Copy, Debug)]
+struct Blizzard {
+    pos: Pos,
+    dir: Pos,
+}
+
+impl Blizzard {
+    fn new(pos: Pos, c: char) -> Option<Self> {
+        let dir = match c {
+            '>' => Pos::RIGHT,
+            'v' => Pos::DOWN,
+            '<' => Pos::LEFT,
+            '^' => Pos::UP,
+            _ => return None,
+        };
+        Some(Self { pos, dir })
+    }
+}
+
+/// Positions are inside the walls, so the entrance is at `y == -1` and the
+/// exit is at `y == height`.
+struct Valley {
+    width: isize,
+    height: isize,
+    blizzards: Vec<Blizzard>,
+    entrance: Pos,
+    exit: Pos,
+}
+
+impl Valley {
+    fn parse(input: Lines) -> Result<Self> {
+        let lines = input.collect_vec();
+        if lines.len() < 3 || lines[0].len() < 3 {
+            return Err(Error::InvalidInput(
+                "valley needs walls around at least one tile".to_string(),
+            ));
+        }
+        let width = lines[0].len() as isize - 2;
+        let height = lines.len() as isize - 2;
+        let gap = |line: usize| {
+            lines[line]
+                .find('.')
+                .map(|x| x as isize - 1)
+                .ok_or_else(|| Error::parse(line + 1, "no gap in the wall"))
+        };
+        let entrance = Pos::new(gap(0)?, -1);
+        let exit = Pos::new(gap(lines.len() - 1)?, height);
+        let mut blizzards = Vec::new();
+        for (y, line) in lines[1..lines.len() - 1].iter().enumerate() {
+            for (x, c) in line.chars().enumerate().skip(1).take(width as usize) {
+                let pos = Pos::new(x as isize - 1, y as isize);
+                match c {
+                    '.' => {}
+                    c => blizzards
+                        .push(Blizzard::new(pos, c).ok_or_else(|| {
+                            Error::parse(y + 2, format!("unexpected tile {c:?}"))
+                        })?),
+                }
+            }
+        }
+        Ok(Self {
+            width,
+            height,
+            blizzards,
+            entrance,
+            exit,
+        })
+    }
+
+    fn blizzards_at(&self, time: usize) -> HashSet<Pos> {
+        let time = time as isize;
+        self.blizzards
+            .iter()
+            .map(|b| {
+                let pos = b.pos + b.dir * time;
+                Pos::new(pos.x.rem_euclid(self.width), pos.y.rem_euclid(self.height))
+            })
+            .collect()
+    }
+
+    /// Minutes after which every blizzard is back where it started.
+    fn period(&self) -> usize {
+        let (width, height) = (self.width as usize, self.height as usize);
+        width / gcd(width, height) * height
+    }
+
+    fn is_open(&self, pos: &Pos, blizzards: &HashSet<Pos>) -> bool {
+        let inside = (0..self.width).contains(&pos.x) && (0..self.height).contains(&pos.y);
+        *pos == self.entrance || *pos == self.exit || (inside && !blizzards.contains(pos))
+    }
+
+    /// Time at which `to` is first reached when leaving `from` at `time`,
+    /// tracking every position the expedition could be in at each minute.
+    fn crossing(&self, from: Pos, to: Pos, time: usize) -> Option<usize> {
+        // blizzards repeat every period, so more minutes than positions in
+        // every blizzard arrangement cannot reach anything new
+        let period = self.period();
+        let arrangements = (0..period).map(|t| self.blizzards_at(t)).collect_vec();
+        let limit = time + period * (self.width * self.height + 2) as usize;
+        let mut reachable = HashSet::from([from]);
+        for time in time + 1..=limit {
+            let blizzards = &arrangements[time % period];
+            reachable = reachable
+                .iter()
+                .flat_map(|&pos| {
+                    Pos::NEIGHBORS4
+                        .map(|offset| pos + offset)
+                        .into_iter()
+                        .chain([pos])
+                })
+                .filter(|pos| self.is_open(pos, blizzards))
+                .collect();
+            if reachable.contains(&to) {
+                return Some(time);
+            }
+        }
+        None
+    }
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+fn no_path() -> Error {
+    Error::NoSolution("blizzards block every path".to_string())
+}
+
+fn part1(input: Lines) -> Result<Answer> {
+    let valley = Valley::parse(input)?;
+    let time = valley
+        .crossing(valley.entrance, valley.exit, 0)
+        .ok_or_else(no_path)?;
+    Ok(time.into())
+}
+
+fn part2(input: Lines) -> Result<Answer> {
+    let valley = Valley::parse(input)?;
+    let trips = [
+        (valley.entrance, valley.exit),
+        (valley.exit, valley.entrance),
+        (valley.entrance, valley.exit),
+    ];
+    let time = trips.iter().try_fold(0, |time, &(from, to)| {
+        valley.crossing(from, to, time).ok_or_else(no_path)
+    })?;
+    Ok(time.into())
+}
+
+pub fn run_parts(runner: &Runner) {
+    let input = include_str!("input.txt");
+    runner.known_answers(include_str!("answers.txt"));
+    runner.run(named!(part1), input);
+    runner.run(named!(part2), input);
+}
+
+fn main() {
+    run_parts(create_runner!());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use advent_of_code::verify;
+
+    #[test]
+    fn test_parse() {
+        let input = include_str!("example.txt");
+        let valley = Valley::parse(input.lines()).unwrap();
+        assert_eq!((valley.width, valley.height), (6, 4));
+        assert_eq!(valley.entrance, Pos::new(0, -1));
+        assert_eq!(valley.exit, Pos::new(5, 4));
+        assert_eq!(valley.blizzards.len(), 19);
+    }
+
+    #[test]
+    fn test_blizzards_at() {
+        let input = "#.#####\n#.....#\n#>....#\n#.....#\n#.....#\n#...v.#\n#.....#\n#####.#";
+        let valley = Valley::parse(input.lines()).unwrap();
+        // both blizzards share a tile after three minutes
+        assert_eq!(valley.blizzards_at(3), HashSet::from([Pos::new(3, 1)]));
+        assert_eq!(
+            valley.blizzards_at(5),
+            HashSet::from([Pos::new(0, 1), Pos::new(3, 3)])
+        );
+        assert_eq!(valley.period(), 30);
+        assert_eq!(valley.blizzards_at(30), valley.blizzards_at(0));
+    }
+
+    #[test]
+    fn example() {
+        let input = include_str!("example.txt");
+        verify!(part1, input, 18);
+        verify!(part2, input, 54);
+    }
+}
@@ -0,0 +1,101 @@
+use advent_of_code::{create_runner, named, Answer, Error, Named, Result, Runner};
+use std::str::Lines;
+
+fn snafu_digit(c: char) -> Option<i64> {
+    match c {
+        '2' => Some(2),
+        '1' => Some(1),
+        '0' => Some(0),
+        '-' => Some(-1),
+        '=' => Some(-2),
+        _ => None,
+    }
+}
+
+fn from_snafu(snafu: &str) -> Option<i64> {
+    snafu
+        .chars()
+        .try_fold(0i64, |value, c| Some(value * 5 + snafu_digit(c)?))
+}
+
+fn to_snafu(mut value: i64) -> String {
+    let mut digits = Vec::new();
+    loop {
+        // shift each digit from 0..5 to -2..3 and carry the difference
+        let digit = (value + 2).rem_euclid(5) - 2;
+        digits.push(match digit {
+            2 => '2',
+            1 => '1',
+            0 => '0',
+            -1 => '-',
+            _ => '=',
+        });
+        value = (value - digit) / 5;
+        if value == 0 {
+            break;
+        }
+    }
+    digits.iter().rev().collect()
+}
+
+fn part1(input: Lines) -> Result<Answer> {
+    let sum = input
+        .enumerate()
+        .map(|(index, line)| {
+            from_snafu(line)
+                .ok_or_else(|| Error::parse(index + 1, format!("invalid SNAFU number {line:?}")))
+        })
+        .sum::<Result<i64>>()?;
+    Ok(to_snafu(sum).into())
+}
+
+pub fn run_parts(runner: &Runner) {
+    let input = include_str!("input.txt");
+    runner.known_answers(include_str!("answers.txt"));
+    runner.run(named!(part1), input);
+}
+
+fn main() {
+    run_parts(create_runner!());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use advent_of_code::verify;
+
+    const EXAMPLES: [(i64, &str); 10] = [
+        (1, "1"),
+        (2, "2"),
+        (3, "1="),
+        (4, "1-"),
+        (5, "10"),
+        (8, "2="),
+        (20, "1-0"),
+        (2022, "1=11-2"),
+        (12345, "1-0---0"),
+        (314159265, "1121-1110-1=0"),
+    ];
+
+    #[test]
+    fn test_from_snafu() {
+        for (value, snafu) in EXAMPLES {
+            assert_eq!(from_snafu(snafu), Some(value), "{snafu}");
+        }
+        assert_eq!(from_snafu("1x"), None);
+    }
+
+    #[test]
+    fn test_to_snafu() {
+        assert_eq!(to_snafu(0), "0");
+        for (value, snafu) in EXAMPLES {
+            assert_eq!(to_snafu(value), snafu, "{value}");
+        }
+    }
+
+    #[test]
+    fn example() {
+        let input = include_str!("example.txt");
+        verify!(part1, input, "2=-1=0");
+    }
+}
@@ -1,21 +1,25 @@
-use advent_of_code::{create_runner, named, Named, Runner};
+use advent_of_code::{create_runner, named, Answer, Named, Result, Runner};
 use std::str::Lines;
 
-fn part1(input: Lines) -> String {
-    input.take(0).count().to_string()
+fn part1(input: Lines) -> Result<Answer> {
+    Ok(input.take(0).count().into())
 }
 
-fn part2(input: Lines) -> String {
-    input.take(0).count().to_string()
+fn part2(input: Lines) -> Result<Answer> {
+    Ok(input.take(0).count().into())
 }
 
-fn main() {
+pub fn run_parts(runner: &Runner) {
     let input = include_str!("input.txt");
-    let runner: &Runner = create_runner!();
+    runner.known_answers(include_str!("answers.txt"));
     runner.run(named!(part1), input);
     runner.run(named!(part2), input);
 }
 
+fn main() {
+    run_parts(create_runner!());
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -24,7 +28,7 @@ mod tests {
     #[test]
     fn example() {
         let input = include_str!("example.txt");
-        verify!(part1, input, "0");
-        verify!(part2, input, "0");
+        verify!(part1, input, 0);
+        verify!(part2, input, 0);
     }
 }
@@ -0,0 +1,167 @@
+use std::{collections::HashMap, hash::Hash};
+
+/// A sequence of states which repeats every `len` steps from step `start` on.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Cycle {
+    pub start: usize,
+    pub len: usize,
+}
+
+impl Cycle {
+    /// The first step whose state is the same as the state at step `n`.
+    pub fn first_equivalent(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.len
+        }
+    }
+}
+
+/// Finds the cycle in `start`, `step(start)`, ... with Brent's algorithm,
+/// which only compares states and keeps two of them at a time. Never returns
+/// if the states never repeat.
+pub fn brent<S: Clone + PartialEq>(start: S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    // the hare moves ahead until it meets the tortoise, which teleports to
+    // the hare after every power of two steps
+    let mut power = 1;
+    let mut len = 1;
+    let mut tortoise = start.clone();
+    let mut hare = step(&start);
+    while tortoise != hare {
+        if power == len {
+            tortoise = hare.clone();
+            power *= 2;
+            len = 0;
+        }
+        hare = step(&hare);
+        len += 1;
+    }
+    // with the hare `len` steps ahead they first meet where the cycle starts
+    let mut tortoise = start.clone();
+    let mut hare = start;
+    for _ in 0..len {
+        hare = step(&hare);
+    }
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+    Cycle { start, len }
+}
+
+/// The state after `n` steps, simulating only until the `key` of a state
+/// repeats. States with the same key must be followed by the same states.
+pub fn nth_state<S, K: Eq + Hash>(
+    start: S,
+    n: usize,
+    mut step: impl FnMut(&S) -> S,
+    mut key: impl FnMut(&S) -> K,
+) -> S {
+    let mut states = vec![start];
+    let mut seen = HashMap::new();
+    loop {
+        let i = states.len() - 1;
+        if i == n {
+            return states.swap_remove(i);
+        }
+        if let Some(first) = seen.insert(key(&states[i]), i) {
+            let cycle = Cycle {
+                start: first,
+                len: i - first,
+            };
+            return states.swap_remove(cycle.first_equivalent(n));
+        }
+        let next = step(&states[i]);
+        states.push(next);
+    }
+}
+
+/// `measure` of `state` after `n` calls of `step`, assuming the measure
+/// changes by the same amount each time the states cycle, like the height
+/// of a growing tower. Steps only until the `key` of a state repeats and
+/// leaves `state` there.
+pub fn extrapolate<S, K: Eq + Hash>(
+    state: &mut S,
+    n: usize,
+    mut step: impl FnMut(&mut S),
+    mut key: impl FnMut(&S) -> K,
+    mut measure: impl FnMut(&S) -> i64,
+) -> i64 {
+    let mut values = Vec::new();
+    let mut seen = HashMap::new();
+    loop {
+        let i = values.len();
+        values.push(measure(state));
+        if i == n {
+            return values[i];
+        }
+        if let Some(first) = seen.insert(key(state), i) {
+            let cycle = Cycle {
+                start: first,
+                len: i - first,
+            };
+            let cycles = ((n - first) / cycle.len) as i64;
+            return values[cycle.first_equivalent(n)] + cycles * (values[i] - values[first]);
+        }
+        step(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 3, 0, 1, 2, 5, 6, 7, 0, ...
+    fn square_plus_one(x: &u32) -> u32 {
+        (x * x + 1) % 10
+    }
+
+    #[test]
+    fn test_brent() {
+        assert_eq!(brent(3, square_plus_one), Cycle { start: 1, len: 6 });
+        assert_eq!(brent(0, square_plus_one), Cycle { start: 0, len: 6 });
+        assert_eq!(brent(4, |&x| x), Cycle { start: 0, len: 1 });
+    }
+
+    #[test]
+    fn test_first_equivalent() {
+        let cycle = Cycle { start: 1, len: 6 };
+        assert_eq!(cycle.first_equivalent(0), 0);
+        assert_eq!(cycle.first_equivalent(6), 6);
+        assert_eq!(cycle.first_equivalent(7), 1);
+        assert_eq!(cycle.first_equivalent(100), 4);
+    }
+
+    #[test]
+    fn test_nth_state() {
+        assert_eq!(nth_state(3, 2, square_plus_one, |&x| x), 1);
+        assert_eq!(nth_state(3, 100, square_plus_one, |&x| x), 5);
+        assert_eq!(nth_state(3, 7, square_plus_one, |&x| x), 0);
+        assert_eq!(nth_state(3, 1_000_000_000_000, square_plus_one, |&x| x), 5);
+    }
+
+    #[test]
+    fn test_extrapolate() {
+        // (position, total) where the position cycles through 0, 1, 2 and
+        // is added to the total after every step
+        let step = |(pos, total): &mut (i64, i64)| {
+            *pos = (*pos + 1) % 3;
+            *total += *pos;
+        };
+        for n in 0..20 {
+            let mut expected = (0, 0);
+            (0..n).for_each(|_| step(&mut expected));
+            let mut state = (0, 0);
+            let total = extrapolate(&mut state, n, step, |&(pos, _)| pos, |&(_, total)| total);
+            assert_eq!(total, expected.1, "after {n} steps");
+        }
+        let mut state = (0, 0);
+        assert_eq!(
+            extrapolate(&mut state, 3_000_000, step, |&(pos, _)| pos, |&(_, t)| t),
+            3_000_000
+        );
+    }
+}
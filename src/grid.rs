@@ -0,0 +1,263 @@
+use crate::{Error, Pos, Result};
+use std::{
+    fmt::Display,
+    ops::{Index, IndexMut},
+};
+
+/// A rectangle of cells stored in row-major order.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// Parses a char map with one line per row, mapping every char with
+    /// `tile`. All rows must have the same width.
+    pub fn parse<'a>(
+        input: impl IntoIterator<Item = &'a str>,
+        mut tile: impl FnMut(char) -> Option<T>,
+    ) -> Result<Self> {
+        let mut rows: Vec<Vec<T>> = Vec::new();
+        for (index, line) in input.into_iter().enumerate() {
+            let row = line
+                .chars()
+                .enumerate()
+                .map(|(column, c)| {
+                    tile(c).ok_or_else(|| {
+                        Error::parse_at(index + 1, column + 1, format!("unexpected tile {c:?}"))
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+            if !rows.is_empty() && row.len() != rows[0].len() {
+                return Err(Error::parse(
+                    index + 1,
+                    format!("expected {} tiles, got {}", rows[0].len(), row.len()),
+                ));
+            }
+            rows.push(row);
+        }
+        Ok(Self::from_rows(rows))
+    }
+
+    /// Builds a grid from rows of equal length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Self {
+        let width = rows.first().map(Vec::len).unwrap_or_default();
+        assert!(
+            rows.iter().all(|row| row.len() == width),
+            "rows have different lengths"
+        );
+        Self {
+            width,
+            height: rows.len(),
+            cells: rows.into_iter().flatten().collect(),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, pos: Pos<usize>) -> bool {
+        pos.x < self.width && pos.y < self.height
+    }
+
+    pub fn get(&self, pos: Pos<usize>) -> Option<&T> {
+        self.contains(pos).then(|| &self.cells[self.index(pos)])
+    }
+
+    pub fn get_mut(&mut self, pos: Pos<usize>) -> Option<&mut T> {
+        if self.contains(pos) {
+            let index = self.index(pos);
+            Some(&mut self.cells[index])
+        } else {
+            None
+        }
+    }
+
+    /// The position `offset` away from `pos`, if it is inside the grid.
+    pub fn offset(&self, pos: Pos<usize>, offset: Pos) -> Option<Pos<usize>> {
+        let pos = Pos::new(
+            pos.x.checked_add_signed(offset.x)?,
+            pos.y.checked_add_signed(offset.y)?,
+        );
+        self.contains(pos).then_some(pos)
+    }
+
+    /// The up to 4 positions which share an edge with `pos`.
+    pub fn neighbors4(&self, pos: Pos<usize>) -> impl Iterator<Item = Pos<usize>> + '_ {
+        Pos::NEIGHBORS4
+            .iter()
+            .filter_map(move |&offset| self.offset(pos, offset))
+    }
+
+    /// The up to 8 positions which share an edge or a corner with `pos`.
+    pub fn neighbors8(&self, pos: Pos<usize>) -> impl Iterator<Item = Pos<usize>> + '_ {
+        Pos::NEIGHBORS8
+            .iter()
+            .filter_map(move |&offset| self.offset(pos, offset))
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item = &[T]> + ExactSizeIterator + '_ {
+        (0..self.height).map(|y| self.row(y))
+    }
+
+    pub fn column(&self, x: usize) -> impl DoubleEndedIterator<Item = &T> + ExactSizeIterator + '_ {
+        assert!(x < self.width, "column {x} out of bounds");
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    /// Every position, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Pos<usize>> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| Pos::new(x, y)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Pos<usize>, &T)> + '_ {
+        self.positions().zip(self.cells.iter())
+    }
+
+    /// The first position, row by row, whose cell satisfies `predicate`.
+    pub fn position(&self, mut predicate: impl FnMut(&T) -> bool) -> Option<Pos<usize>> {
+        self.iter()
+            .find_map(|(pos, cell)| predicate(cell).then_some(pos))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// Adds rows of `value` at the bottom until the grid is `height` tall.
+    pub fn grow(&mut self, height: usize, value: T)
+    where
+        T: Clone,
+    {
+        if height > self.height {
+            self.cells.resize(height * self.width, value);
+            self.height = height;
+        }
+    }
+
+    /// One line per row, turning each cell into a char with `tile`.
+    pub fn render(&self, mut tile: impl FnMut(&T) -> char) -> Vec<String> {
+        self.rows()
+            .map(|row| row.iter().map(&mut tile).collect())
+            .collect()
+    }
+
+    fn index(&self, pos: Pos<usize>) -> usize {
+        pos.y * self.width + pos.x
+    }
+}
+
+impl<T> Index<Pos<usize>> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos<usize>) -> &T {
+        self.get(pos)
+            .unwrap_or_else(|| panic!("{pos:?} out of bounds"))
+    }
+}
+
+impl<T> IndexMut<Pos<usize>> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos<usize>) -> &mut T {
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("{pos:?} out of bounds"))
+    }
+}
+
+impl Display for Grid<char> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.render(|&c| c).join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Grid<char> {
+        Grid::parse("ab.\n.#c".lines(), Some).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = example();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[Pos::new(1, 1)], '#');
+        assert_eq!(grid.to_string(), "ab.\n.#c");
+        assert_eq!(
+            Grid::parse("12\n3".lines(), |c| c.to_digit(10)),
+            Err(Error::parse(2, "expected 2 tiles, got 1"))
+        );
+        assert_eq!(
+            Grid::parse("12\n3x".lines(), |c| c.to_digit(10)),
+            Err(Error::parse_at(2, 2, "unexpected tile 'x'"))
+        );
+        assert_eq!(Grid::<char>::parse("".lines(), Some).unwrap().height(), 0);
+    }
+
+    #[test]
+    fn test_access() {
+        let mut grid = example();
+        assert_eq!(grid.get(Pos::new(2, 1)), Some(&'c'));
+        assert_eq!(grid.get(Pos::new(3, 0)), None);
+        assert_eq!(grid.get(Pos::new(0, 2)), None);
+        grid[Pos::new(2, 0)] = 'x';
+        assert_eq!(grid.row(0), ['a', 'b', 'x']);
+        assert_eq!(grid.column(1).collect::<String>(), "b#");
+        assert_eq!(grid.rows().next_back(), Some(&['.', '#', 'c'][..]));
+        assert_eq!(grid.position(|&c| c == '#'), Some(Pos::new(1, 1)));
+        assert_eq!(grid.iter().nth(4), Some((Pos::new(1, 1), &'#')));
+        grid.grow(3, '~');
+        assert_eq!(grid.render(|&c| c), ["abx", ".#c", "~~~"]);
+        assert_eq!(
+            grid.map(|&c| c == '#').iter().filter(|(_, &b)| b).count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let grid = Grid::new(3, 3, 0);
+        assert_eq!(
+            grid.neighbors4(Pos::new(0, 0)).collect::<Vec<_>>(),
+            [Pos::new(1, 0), Pos::new(0, 1)]
+        );
+        assert_eq!(grid.neighbors4(Pos::new(1, 1)).count(), 4);
+        assert_eq!(grid.neighbors8(Pos::new(1, 1)).count(), 8);
+        assert_eq!(
+            grid.neighbors8(Pos::new(2, 2)).collect::<Vec<_>>(),
+            [Pos::new(1, 1), Pos::new(2, 1), Pos::new(1, 2)]
+        );
+        assert_eq!(
+            grid.offset(Pos::new(1, 1), Pos::new(1, -1)),
+            Some(Pos::new(2, 0))
+        );
+        assert_eq!(grid.offset(Pos::new(1, 1), Pos::new(2, 0)), None);
+    }
+}
@@ -0,0 +1,320 @@
+use crate::{Grid, Pos};
+use std::{
+    cell::RefCell,
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+pub type Rgb = [u8; 3];
+
+/// A picture in RGB, which can be saved as PPM or PNG.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<Rgb>,
+}
+
+impl Image {
+    pub fn new(width: usize, height: usize, color: Rgb) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![color; width * height],
+        }
+    }
+
+    /// One pixel per cell of `grid`, colored by `color`.
+    pub fn from_grid<T>(grid: &Grid<T>, mut color: impl FnMut(&T) -> Rgb) -> Self {
+        Self {
+            width: grid.width(),
+            height: grid.height(),
+            pixels: grid.iter().map(|(_, cell)| color(cell)).collect(),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, pos: Pos<usize>) -> Option<Rgb> {
+        (pos.x < self.width && pos.y < self.height).then(|| self.pixels[pos.y * self.width + pos.x])
+    }
+
+    /// Sets a pixel, ignoring positions outside the image.
+    pub fn set(&mut self, pos: Pos<usize>, color: Rgb) {
+        if pos.x < self.width && pos.y < self.height {
+            self.pixels[pos.y * self.width + pos.x] = color;
+        }
+    }
+
+    /// Mirrors the rows, for grids where `y` counts up.
+    pub fn flip_vertical(&self) -> Self {
+        let pixels = self
+            .pixels
+            .chunks(self.width.max(1))
+            .rev()
+            .flatten()
+            .copied()
+            .collect();
+        Self { pixels, ..*self }
+    }
+
+    /// Turns every pixel into a `factor` by `factor` square.
+    pub fn scale(&self, factor: usize) -> Self {
+        let mut pixels = Vec::with_capacity(self.pixels.len() * factor * factor);
+        for row in self.pixels.chunks(self.width.max(1)) {
+            let scaled_row = row
+                .iter()
+                .flat_map(|&pixel| std::iter::repeat_n(pixel, factor))
+                .collect::<Vec<_>>();
+            for _ in 0..factor {
+                pixels.extend_from_slice(&scaled_row);
+            }
+        }
+        Self {
+            width: self.width * factor,
+            height: self.height * factor,
+            pixels,
+        }
+    }
+
+    /// Binary PPM (P6), which most image viewers and converters read.
+    pub fn to_ppm(&self) -> Vec<u8> {
+        let mut bytes = format!("P6\n{} {}\n255\n", self.width, self.height).into_bytes();
+        bytes.extend(self.pixels.iter().flatten());
+        bytes
+    }
+
+    /// PNG with uncompressed image data, so no compression library is needed.
+    pub fn to_png(&self) -> Vec<u8> {
+        let mut header = Vec::new();
+        header.extend((self.width as u32).to_be_bytes());
+        header.extend((self.height as u32).to_be_bytes());
+        // 8 bits per channel, RGB, default compression, filtering and no interlace
+        header.extend([8, 2, 0, 0, 0]);
+        // every row starts with filter type 0, meaning none
+        let mut raw = Vec::with_capacity(self.height * (1 + 3 * self.width));
+        for row in self.pixels.chunks(self.width.max(1)).take(self.height) {
+            raw.push(0);
+            raw.extend(row.iter().flatten());
+        }
+        let mut bytes = b"\x89PNG\r\n\x1a\n".to_vec();
+        png_chunk(&mut bytes, b"IHDR", &header);
+        png_chunk(&mut bytes, b"IDAT", &zlib_stored(&raw));
+        png_chunk(&mut bytes, b"IEND", &[]);
+        bytes
+    }
+
+    pub fn save(&self, path: &Path, format: ImageFormat) -> io::Result<()> {
+        let bytes = match format {
+            ImageFormat::Ppm => self.to_ppm(),
+            ImageFormat::Png => self.to_png(),
+        };
+        fs::File::create(path)?.write_all(&bytes)
+    }
+}
+
+fn png_chunk(bytes: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    bytes.extend((data.len() as u32).to_be_bytes());
+    let start = bytes.len();
+    bytes.extend(kind);
+    bytes.extend(data);
+    let crc = crc32(&bytes[start..]);
+    bytes.extend(crc.to_be_bytes());
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb88320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+/// A zlib stream of deflate blocks which store `data` as is.
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut bytes = vec![0x78, 0x01];
+    let mut blocks = data.chunks(u16::MAX as usize).peekable();
+    if blocks.peek().is_none() {
+        bytes.extend([1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        let last = blocks.peek().is_none();
+        bytes.push(last as u8);
+        bytes.extend((block.len() as u16).to_le_bytes());
+        bytes.extend((!(block.len() as u16)).to_le_bytes());
+        bytes.extend(block);
+    }
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in data {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    bytes.extend(((b << 16) | a).to_be_bytes());
+    bytes
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ImageFormat {
+    Ppm,
+    Png,
+}
+
+impl ImageFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            ImageFormat::Ppm => "ppm",
+            ImageFormat::Png => "png",
+        }
+    }
+}
+
+impl FromStr for ImageFormat {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ppm" => Ok(ImageFormat::Ppm),
+            "png" => Ok(ImageFormat::Png),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Types which can be drawn as a picture for `snapshot`.
+pub trait ToImage {
+    fn to_image(&self) -> Image;
+}
+
+/// Where `snapshot` saves frames, named after the part which took them.
+struct Exporter {
+    dir: PathBuf,
+    format: ImageFormat,
+    name: String,
+    frames: usize,
+}
+
+// kept per thread, so that runners working at once each save their own part
+thread_local! {
+    static EXPORTER: RefCell<Option<Exporter>> = const { RefCell::new(None) };
+}
+
+/// Whether `snapshot` saves images on this thread, which `Runner` turns on
+/// for `--images` while a part runs.
+pub fn exporting() -> bool {
+    EXPORTER.with_borrow(Option::is_some)
+}
+
+/// Saves the snapshots taken from now on to `dir`, as `<name>_<frame>`.
+pub(crate) fn start_export(dir: &Path, format: ImageFormat, name: String) {
+    EXPORTER.set(Some(Exporter {
+        dir: dir.to_path_buf(),
+        format,
+        name,
+        frames: 0,
+    }));
+}
+
+pub(crate) fn stop_export() {
+    EXPORTER.set(None);
+}
+
+/// Saves `picture` as the next frame when exporting. Failing to save is
+/// reported on stderr rather than failing the part.
+pub fn snapshot(picture: &impl ToImage) {
+    EXPORTER.with_borrow_mut(|exporter| {
+        let Some(exporter) = exporter.as_mut() else {
+            return;
+        };
+        let file = format!(
+            "{}_{:04}.{}",
+            exporter.name,
+            exporter.frames,
+            exporter.format.extension()
+        );
+        let path = exporter.dir.join(file);
+        exporter.frames += 1;
+        let saved = fs::create_dir_all(&exporter.dir)
+            .and_then(|_| picture.to_image().save(&path, exporter.format));
+        if let Err(e) = saved {
+            eprintln!("failed to save {}: {e}", path.display());
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: Rgb = [255, 0, 0];
+    const BLACK: Rgb = [0, 0, 0];
+
+    fn example() -> Image {
+        let grid = Grid::from_rows(vec![vec![true, false], vec![false, false]]);
+        Image::from_grid(&grid, |&on| if on { RED } else { BLACK })
+    }
+
+    #[test]
+    fn test_pixels() {
+        let mut image = example();
+        assert_eq!((image.width(), image.height()), (2, 2));
+        assert_eq!(image.get(Pos::new(0, 0)), Some(RED));
+        assert_eq!(image.get(Pos::new(2, 0)), None);
+        image.set(Pos::new(1, 1), RED);
+        image.set(Pos::new(5, 5), RED);
+        assert_eq!(image.get(Pos::new(1, 1)), Some(RED));
+        let flipped = example().flip_vertical();
+        assert_eq!(flipped.get(Pos::new(0, 1)), Some(RED));
+        let scaled = example().scale(3);
+        assert_eq!((scaled.width(), scaled.height()), (6, 6));
+        assert_eq!(scaled.get(Pos::new(2, 2)), Some(RED));
+        assert_eq!(scaled.get(Pos::new(3, 2)), Some(BLACK));
+    }
+
+    #[test]
+    fn test_ppm() {
+        let ppm = example().to_ppm();
+        assert!(ppm.starts_with(b"P6\n2 2\n255\n"));
+        assert_eq!(&ppm[11..], &[255, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_png() {
+        assert_eq!(crc32(b"IEND"), 0xae426082);
+        assert_eq!(
+            &zlib_stored(b"Wikipedia")[16..],
+            &0x11e60398u32.to_be_bytes()
+        );
+        let png = example().to_png();
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR\0\0\0\x02\0\0\0\x02"));
+        assert!(png.ends_with(b"\0\0\0\0IEND\xae\x42\x60\x82"));
+    }
+
+    #[test]
+    fn test_zlib_stored() {
+        let data = vec![7; 70000];
+        let bytes = zlib_stored(&data);
+        // two blocks of five header bytes each around the data
+        assert_eq!(bytes.len(), 2 + 5 + 65535 + 5 + 4465 + 4);
+        assert_eq!(bytes[2], 0);
+        assert_eq!(bytes[2 + 5 + 65535], 1);
+        assert_eq!(
+            zlib_stored(&[]),
+            [0x78, 0x01, 1, 0, 0, 0xff, 0xff, 0, 0, 0, 1]
+        );
+    }
+}
@@ -0,0 +1,269 @@
+use std::ops::{Add, Range, Sub};
+
+/// A set of values stored as sorted, disjoint half-open ranges. Ranges which
+/// overlap or touch are merged, so each gap between ranges is non-empty.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct IntervalSet<T> {
+    ranges: Vec<Range<T>>,
+}
+
+impl<T> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self { ranges: Vec::new() }
+    }
+}
+
+impl<T: Copy + Ord> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn ranges(&self) -> &[Range<T>] {
+        &self.ranges
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Adds every value of `range`, merging it with the ranges it reaches.
+    pub fn insert(&mut self, range: Range<T>) {
+        if range.is_empty() {
+            return;
+        }
+        // ranges[first..last] overlap or touch the new range
+        let first = self.ranges.partition_point(|r| r.end < range.start);
+        let last = self.ranges.partition_point(|r| r.start <= range.end);
+        let merged = match self.ranges.get(first..last) {
+            Some([a, .., b]) | Some([a @ b]) => a.start.min(range.start)..b.end.max(range.end),
+            _ => range,
+        };
+        self.ranges.splice(first..last, [merged]);
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let i = self.ranges.partition_point(|r| r.end <= value);
+        self.ranges.get(i).is_some_and(|r| r.start <= value)
+    }
+
+    /// Whether every value of `range` is in the set.
+    pub fn contains_range(&self, range: &Range<T>) -> bool {
+        if range.is_empty() {
+            return true;
+        }
+        let i = self.ranges.partition_point(|r| r.end <= range.start);
+        self.ranges
+            .get(i)
+            .is_some_and(|r| r.start <= range.start && range.end <= r.end)
+    }
+
+    pub fn is_superset(&self, other: &Self) -> bool {
+        other.ranges.iter().all(|range| self.contains_range(range))
+    }
+
+    /// Whether any value is in both sets.
+    pub fn overlaps(&self, other: &Self) -> bool {
+        !self.intersection(other).is_empty()
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut union = self.clone();
+        for range in &other.ranges {
+            union.insert(range.clone());
+        }
+        union
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let (mut a, mut b) = (
+            self.ranges.iter().peekable(),
+            other.ranges.iter().peekable(),
+        );
+        let mut ranges = Vec::new();
+        while let (Some(x), Some(y)) = (a.peek(), b.peek()) {
+            let (start, end) = (x.start.max(y.start), x.end.min(y.end));
+            if start < end {
+                ranges.push(start..end);
+            }
+            // the range ending first can't overlap anything further
+            if x.end < y.end {
+                a.next();
+            } else {
+                b.next();
+            }
+        }
+        Self { ranges }
+    }
+
+    /// The values of `bounds` which are not in the set.
+    pub fn complement(&self, bounds: Range<T>) -> Self {
+        let mut ranges = Vec::new();
+        if bounds.is_empty() {
+            return Self { ranges };
+        }
+        let mut start = bounds.start;
+        for range in &self.ranges {
+            if range.start > start {
+                ranges.push(start..range.start.min(bounds.end));
+            }
+            start = start.max(range.end);
+            if start >= bounds.end {
+                break;
+            }
+        }
+        if start < bounds.end {
+            ranges.push(start..bounds.end);
+        }
+        Self { ranges }
+    }
+
+    /// The values in the set which are not in `other`.
+    pub fn difference(&self, other: &Self) -> Self {
+        match (self.ranges.first(), self.ranges.last()) {
+            (Some(first), Some(last)) => {
+                self.intersection(&other.complement(first.start..last.end))
+            }
+            _ => Self::new(),
+        }
+    }
+}
+
+impl<T: Copy + Ord + Default + Add<Output = T> + Sub<Output = T>> IntervalSet<T> {
+    /// How many values are in the set.
+    pub fn len(&self) -> T {
+        self.ranges
+            .iter()
+            .fold(T::default(), |len, range| len + (range.end - range.start))
+    }
+}
+
+impl<T: Copy + Ord> From<Range<T>> for IntervalSet<T> {
+    fn from(range: Range<T>) -> Self {
+        let mut set = Self::new();
+        set.insert(range);
+        set
+    }
+}
+
+impl<T: Copy + Ord> FromIterator<Range<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Range<T>>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for range in iter {
+            set.insert(range);
+        }
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn test_insert() {
+        let mut set = IntervalSet::new();
+        set.insert(5..8);
+        set.insert(0..2);
+        set.insert(3..3);
+        assert_eq!(set.ranges(), &[0..2, 5..8]);
+        set.insert(2..4);
+        assert_eq!(set.ranges(), &[0..4, 5..8]);
+        set.insert(4..5);
+        assert_eq!(set, IntervalSet::from(0..8));
+        set.insert(10..12);
+        set.insert(1..11);
+        assert_eq!(set, IntervalSet::from(0..12));
+        assert_eq!(set.len(), 12);
+    }
+
+    #[test]
+    fn test_queries() {
+        let set = IntervalSet::from_iter([0..3, 5..8]);
+        assert!(set.contains(0) && set.contains(7));
+        assert!(!set.contains(3) && !set.contains(-1) && !set.contains(8));
+        assert!(set.contains_range(&(5..8)) && set.contains_range(&(9..9)));
+        assert!(!set.contains_range(&(2..6)));
+        assert!(set.is_superset(&IntervalSet::from_iter([1..2, 6..8])));
+        assert!(set.overlaps(&IntervalSet::from(7..10)));
+        assert!(!set.overlaps(&IntervalSet::from(3..5)));
+    }
+
+    #[test]
+    fn test_algebra() {
+        let a = IntervalSet::from_iter([0..3, 5..8]);
+        let b = IntervalSet::from_iter([2..6, 7..9]);
+        assert_eq!(a.union(&b), IntervalSet::from(0..9));
+        assert_eq!(a.intersection(&b).ranges(), &[2..3, 5..6, 7..8]);
+        assert_eq!(a.difference(&b).ranges(), &[0..2, 6..7]);
+        assert_eq!(a.complement(-2..10).ranges(), &[-2..0, 3..5, 8..10]);
+        assert_eq!(a.complement(1..6), IntervalSet::from(3..5));
+        assert_eq!(a.complement(4..4).ranges(), &[]);
+        assert_eq!(IntervalSet::new().complement(1..3), IntervalSet::from(1..3));
+    }
+
+    /// A xorshift generator, so that property tests are repeatable.
+    struct Rng(u64);
+
+    impl Rng {
+        fn below(&mut self, n: i64) -> i64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            (self.0 % n as u64) as i64
+        }
+
+        fn set(&mut self) -> IntervalSet<i64> {
+            (0..self.below(5))
+                .map(|_| {
+                    let start = self.below(UNIVERSE);
+                    start..start + self.below(8)
+                })
+                .collect()
+        }
+    }
+
+    const UNIVERSE: i64 = 40;
+
+    fn values(set: &IntervalSet<i64>) -> BTreeSet<i64> {
+        set.ranges().iter().flat_map(Range::clone).collect()
+    }
+
+    fn is_normal(set: &IntervalSet<i64>) -> bool {
+        let ranges = set.ranges();
+        ranges.iter().all(|r| r.start < r.end) && ranges.windows(2).all(|w| w[0].end < w[1].start)
+    }
+
+    #[test]
+    fn test_properties() {
+        let mut rng = Rng(0x2545f4914f6cdd1d);
+        for _ in 0..2000 {
+            let (a, b) = (rng.set(), rng.set());
+            let (va, vb) = (values(&a), values(&b));
+            let bounds = rng.below(UNIVERSE)..rng.below(UNIVERSE + 10);
+            let results = [
+                (a.union(&b), &va | &vb),
+                (a.intersection(&b), &va & &vb),
+                (a.difference(&b), &va - &vb),
+                (
+                    a.complement(bounds.clone()),
+                    bounds.clone().filter(|v| !va.contains(v)).collect(),
+                ),
+            ];
+            for (set, expected) in results {
+                assert!(is_normal(&set), "{set:?}");
+                assert_eq!(values(&set), expected, "{a:?} {b:?} {bounds:?}");
+            }
+            assert_eq!(a.len(), va.len() as i64);
+            assert_eq!(a.is_superset(&b), vb.is_subset(&va));
+            assert_eq!(a.overlaps(&b), !va.is_disjoint(&vb));
+            assert_eq!(
+                a.contains_range(&bounds),
+                bounds.clone().all(|v| va.contains(&v))
+            );
+            for v in -2..UNIVERSE + 10 {
+                assert_eq!(a.contains(v), va.contains(&v));
+            }
+        }
+    }
+}
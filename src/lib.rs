@@ -1,4 +1,39 @@
-use std::{collections::HashSet, str::Lines};
+mod answer;
+mod cycle;
+mod grid;
+mod image;
+mod interval;
+mod ocr;
+mod parallel;
+mod parse;
+mod pos;
+mod render;
+mod search;
+
+pub use answer::Answer;
+pub use cycle::{brent, extrapolate, nth_state, Cycle};
+pub use grid::Grid;
+pub use image::{exporting, snapshot, Image, ImageFormat, Rgb, ToImage};
+pub use interval::IntervalSet;
+pub use ocr::ocr;
+pub use parallel::{par_find_map, par_map, parallel};
+pub use parse::{captures, integers, parse_lines, sections, FromCaptures, LineError, Section};
+pub use pos::{ParsePosError, Pos, Pos3};
+pub use render::{trace, tracing, Render};
+
+use render::Playback;
+pub use search::{all_pairs, astar, bfs, bfs_multi, dijkstra, AllPairs, Search};
+
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    io::Read,
+    path::PathBuf,
+    str::{FromStr, Lines},
+    sync::Once,
+    time::{Duration, Instant},
+};
 
 pub struct Named<T> {
     pub wrapped: T,
@@ -21,26 +56,653 @@ macro_rules! named {
 pub struct Runner {
     module_name: &'static str,
     operations: HashSet<String>,
+    input: Option<String>,
+    listing: bool,
+    bench: Option<usize>,
+    playback: Playback,
+    steps: Option<usize>,
+    images: Option<PathBuf>,
+    image_format: ImageFormat,
+    parallel: bool,
+    format: Format,
+    /// Collects the printed results instead, when set by `buffered`.
+    output: RefCell<Option<String>>,
+    answers: RefCell<Option<Answers>>,
+    timings: RefCell<Vec<Timing>>,
+    statuses: RefCell<Vec<Status>>,
+}
+
+type Operation = fn(Lines) -> Result<Answer>;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Error {
+    /// Input that could not be parsed, `line` and `column` count from 1.
+    Parse {
+        line: usize,
+        column: Option<usize>,
+        message: String,
+    },
+    /// Input was parsed, but no answer satisfies the puzzle.
+    NoSolution(String),
+    /// Input was parsed, but is inconsistent or incomplete.
+    InvalidInput(String),
+    /// The operation panicked, only produced by `Runner`.
+    Panic(String),
 }
 
-type Operation = fn(Lines) -> String;
+impl Error {
+    pub fn parse(line: usize, message: impl Into<String>) -> Self {
+        Error::Parse {
+            line,
+            column: None,
+            message: message.into(),
+        }
+    }
+
+    pub fn parse_at(line: usize, column: usize, message: impl Into<String>) -> Self {
+        Error::Parse {
+            line,
+            column: Some(column),
+            message: message.into(),
+        }
+    }
+
+    /// Moves a parse error down by `lines`, for errors from parsing a part
+    /// of the input which did not start on line 1.
+    pub fn offset_lines(self, lines: usize) -> Self {
+        match self {
+            Error::Parse {
+                line,
+                column,
+                message,
+            } => Error::Parse {
+                line: line + lines,
+                column,
+                message,
+            },
+            error => error,
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Parse {
+                line,
+                column: None,
+                message,
+            } => write!(f, "parse error on line {line}: {message}"),
+            Error::Parse {
+                line,
+                column: Some(column),
+                message,
+            } => write!(f, "parse error on line {line}, column {column}: {message}"),
+            Error::NoSolution(message) => write!(f, "no solution: {message}"),
+            Error::InvalidInput(message) => write!(f, "invalid input: {message}"),
+            Error::Panic(message) => write!(f, "panicked: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+fn call(op: &Named<Operation>, input: &str) -> Result<Answer> {
+    std::panic::catch_unwind(|| (op.wrapped)(input.lines())).unwrap_or_else(|panic| {
+        let message = panic
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| panic.downcast_ref::<String>().cloned())
+            .unwrap_or_default();
+        Err(Error::Panic(message))
+    })
+}
 
 impl Runner {
     pub fn create(module_name: &'static str) -> Self {
+        Self::from_args(module_name, std::env::args().skip(1))
+    }
+
+    /// Operation names select which parts run, `--input <path>` (or `-i`)
+    /// replaces the embedded input, with `-` meaning stdin, and `--bench`
+    /// (or `--bench=<runs>`) times each part over repeated runs.
+    /// `--format <text|json|csv>` selects how results are printed.
+    /// `--answers <path>` supplies known answers to check results against.
+    /// `--trace` prints the frames which parts pass to `trace`, while
+    /// `--play` (or `--fps=<rate>`) animates them in the terminal, and
+    /// `--steps=<count>` limits how many frames either shows.
+    /// `--images <dir>` saves the frames which parts pass to `snapshot`, as
+    /// PNG or as set by `--image-format <png|ppm>`.
+    /// `--parallel` lets parts spread work over threads with `par_map` and
+    /// `par_find_map`.
+    pub fn from_args<I: IntoIterator<Item = String>>(module_name: &'static str, args: I) -> Self {
+        let mut operations = HashSet::new();
+        let mut input = None;
+        let mut bench = None;
+        let mut trace = false;
+        let mut play = false;
+        let mut fps = None;
+        let mut steps = None;
+        let mut images = None;
+        let mut image_format = ImageFormat::Png;
+        let mut parallel = false;
+        let mut format = Format::Text;
+        let mut answers = None;
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            if arg == "--input" || arg == "-i" {
+                let path = args
+                    .next()
+                    .unwrap_or_else(|| panic!("{arg} requires a path"));
+                input = Some(read_input(&path));
+            } else if let Some(path) = arg.strip_prefix("--input=") {
+                input = Some(read_input(path));
+            } else if arg == "--bench" {
+                bench = Some(DEFAULT_BENCH_RUNS);
+            } else if let Some(runs) = arg.strip_prefix("--bench=") {
+                let runs = runs
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|&runs| runs > 0)
+                    .unwrap_or_else(|| panic!("invalid number of runs {runs}"));
+                bench = Some(runs);
+            } else if arg == "--trace" {
+                trace = true;
+            } else if arg == "--play" {
+                play = true;
+            } else if let Some(rate) = arg.strip_prefix("--fps=") {
+                let rate = rate
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|&rate| rate > 0)
+                    .unwrap_or_else(|| panic!("invalid frame rate {rate}"));
+                fps = Some(rate);
+            } else if let Some(count) = arg.strip_prefix("--steps=") {
+                let count = count
+                    .parse::<usize>()
+                    .unwrap_or_else(|_| panic!("invalid number of steps {count}"));
+                steps = Some(count);
+            } else if arg == "--images" {
+                let dir = args
+                    .next()
+                    .unwrap_or_else(|| panic!("{arg} requires a directory"));
+                images = Some(PathBuf::from(dir));
+            } else if let Some(dir) = arg.strip_prefix("--images=") {
+                images = Some(PathBuf::from(dir));
+            } else if arg == "--image-format" {
+                let value = args
+                    .next()
+                    .unwrap_or_else(|| panic!("{arg} requires a format"));
+                image_format = parse_image_format(&value);
+            } else if let Some(value) = arg.strip_prefix("--image-format=") {
+                image_format = parse_image_format(value);
+            } else if arg == "--parallel" {
+                parallel = true;
+            } else if arg == "--format" {
+                let value = args
+                    .next()
+                    .unwrap_or_else(|| panic!("{arg} requires a format"));
+                format = Format::parse(&value);
+            } else if let Some(value) = arg.strip_prefix("--format=") {
+                format = Format::parse(value);
+            } else if arg == "--answers" {
+                let path = args
+                    .next()
+                    .unwrap_or_else(|| panic!("{arg} requires a path"));
+                answers = Some(parse_answers(&read_input(&path)));
+            } else if let Some(path) = arg.strip_prefix("--answers=") {
+                answers = Some(parse_answers(&read_input(path)));
+            } else {
+                operations.insert(arg);
+            }
+        }
+        let playback = if play || fps.is_some() {
+            Playback::Animate {
+                fps: fps.unwrap_or(DEFAULT_FPS),
+            }
+        } else if trace {
+            Playback::Trace
+        } else {
+            Playback::Off
+        };
         Self {
             module_name,
-            operations: std::env::args().skip(1).collect(),
+            operations,
+            input,
+            listing: false,
+            bench,
+            playback,
+            steps,
+            images,
+            image_format,
+            parallel,
+            format,
+            output: RefCell::new(None),
+            answers: RefCell::new(answers),
+            timings: RefCell::new(Vec::new()),
+            statuses: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Prints the name of each operation instead of running it.
+    pub fn listing(module_name: &'static str) -> Self {
+        Self {
+            listing: true,
+            ..Self::from_args(module_name, [])
+        }
+    }
+
+    /// Keeps the printed results for `take_output`, so that several runners
+    /// can work at once and still be shown in order. The CSV header is left
+    /// to the caller, with `Format::print_header`.
+    pub fn buffered(self) -> Self {
+        Self {
+            output: RefCell::new(Some(String::new())),
+            ..self
+        }
+    }
+
+    /// The results printed since the last call, when `buffered`.
+    pub fn take_output(&self) -> String {
+        self.output
+            .borrow_mut()
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
+    fn print(&self, text: impl Display) {
+        match self.output.borrow_mut().as_mut() {
+            Some(output) => {
+                output.push_str(&text.to_string());
+                output.push('\n');
+            }
+            None => println!("{text}"),
+        }
+    }
+
+    pub fn format(&self) -> Format {
+        self.format
+    }
+
+    /// Whether `--parallel` was given and nothing needs the parts to run one
+    /// at a time. Traced frames and exported images belong to a single part,
+    /// so they keep everything sequential.
+    pub fn parallel(&self) -> bool {
+        self.parallel && self.playback == Playback::Off && self.images.is_none()
+    }
+
+    /// Known answers for the embedded input, one `name: answer` per line, or
+    /// `name:` followed by the lines of a multi-line answer. Ignored when
+    /// `--input` or `--answers` was given on the command line.
+    pub fn known_answers(&self, answers: &str) {
+        let mut known = self.answers.borrow_mut();
+        if self.input.is_none() && known.is_none() {
+            *known = Some(parse_answers(answers));
         }
     }
 
     pub fn run(&self, op: &Named<Operation>, input: &str) {
+        if self.listing {
+            self.print(format_args!("{} {}", self.module_name, op.name));
+            return;
+        }
+        // a day whose input wasn't committed is skipped rather than failed
+        let missing = self.input.is_none() && input.is_empty();
+        let input = self.input.as_deref().unwrap_or(input);
         let enabled: bool = self.operations.is_empty() || self.operations.contains(op.name);
-        let result: String = if enabled {
-            (op.wrapped)(input.lines())
+        let (record, timing) = if enabled && !missing {
+            let runs = self.bench.unwrap_or(1);
+            let mut durations = Vec::with_capacity(runs);
+            let mut result = Ok(Answer::Text(String::new()));
+            parallel::set_parallel(self.parallel());
+            render::start(self.playback, self.steps);
+            if let Some(dir) = &self.images {
+                let name = format!("{}_{}", self.module_name, op.name);
+                image::start_export(dir, self.image_format, name);
+            }
+            for _ in 0..runs {
+                let start = Instant::now();
+                result = call(op, input);
+                durations.push(start.elapsed());
+                if result.is_err() {
+                    break;
+                }
+            }
+            parallel::set_parallel(false);
+            render::stop();
+            image::stop_export();
+            let timing = self
+                .bench
+                .filter(|_| result.is_ok())
+                .map(|_| Timing::new(self.module_name, op.name, durations.clone()));
+            let duration = timing.as_ref().map_or(durations[0], |t| t.median);
+            let status = match (&result, self.answers.borrow().as_ref()) {
+                (Err(_), _) => Status::Error,
+                (Ok(result), Some(answers)) => match answers.get(op.name) {
+                    Some(expected) if result.matches(expected) => Status::Pass,
+                    Some(_) => Status::Fail,
+                    None => Status::Unknown,
+                },
+                (Ok(_), None) => Status::Unknown,
+            };
+            self.statuses.borrow_mut().push(status);
+            let (answer, error) = match result {
+                Ok(answer) => (Some(answer), None),
+                Err(error) => (None, Some(error)),
+            };
+            let record = Record {
+                module_name: self.module_name,
+                name: op.name,
+                answer,
+                error,
+                status: Some(status),
+                duration: Some(duration),
+            };
+            (record, timing)
         } else {
-            String::from("(DISABLED)")
+            let record = Record {
+                module_name: self.module_name,
+                name: op.name,
+                answer: None,
+                error: None,
+                status: None,
+                duration: None,
+            };
+            (record, None)
         };
-        println!("{} {}:\n{}", self.module_name, op.name, result);
+        let result = match (&record.answer, &record.error) {
+            (Some(answer), _) => answer.to_string(),
+            (None, Some(error)) => error.to_string(),
+            (None, None) if enabled => String::from("(NO INPUT)"),
+            (None, None) => String::from("(DISABLED)"),
+        };
+        match self.format {
+            Format::Text => {
+                match record.status {
+                    Some(status) => {
+                        self.print(format_args!("{} {}: {status}", self.module_name, op.name))
+                    }
+                    None => self.print(format_args!("{} {}:", self.module_name, op.name)),
+                }
+                self.print(&result);
+                if record.status == Some(Status::Fail) {
+                    let answers = self.answers.borrow();
+                    let expected = &answers.as_ref().unwrap()[op.name];
+                    self.print(format_args!("expected:\n{expected}"));
+                }
+                if let Some(timing) = &timing {
+                    self.print(timing);
+                }
+            }
+            Format::Json => self.print(record.to_json()),
+            Format::Csv => {
+                if self.output.borrow().is_none() {
+                    self.format.print_header();
+                }
+                self.print(record.to_csv());
+            }
+        }
+        if let Some(timing) = timing {
+            self.timings.borrow_mut().push(timing);
+        }
+    }
+
+    /// Status of each enabled operation checked against the known answers.
+    pub fn statuses(&self) -> Vec<Status> {
+        self.statuses.borrow().clone()
+    }
+
+    /// Timings of each enabled operation, only collected with `--bench`.
+    pub fn into_timings(self) -> Vec<Timing> {
+        self.timings.into_inner()
+    }
+}
+
+const DEFAULT_BENCH_RUNS: usize = 10;
+
+const DEFAULT_FPS: u32 = 10;
+
+fn parse_image_format(value: &str) -> ImageFormat {
+    value
+        .parse()
+        .unwrap_or_else(|_| panic!("unknown image format {value}, expected png or ppm"))
+}
+
+static CSV_HEADER: Once = Once::new();
+
+type Answers = HashMap<String, String>;
+
+fn parse_answers(text: &str) -> Answers {
+    let mut answers = Answers::new();
+    let mut current: Option<&mut String> = None;
+    for line in text.lines() {
+        let entry = line.split_once(':').filter(|(name, _)| {
+            !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+        });
+        if let Some((name, value)) = entry {
+            let answer = answers.entry(name.to_string()).or_default();
+            answer.push_str(value.trim());
+            current = Some(answer);
+        } else if let Some(answer) = current.as_mut() {
+            answer.push_str(line);
+            answer.push('\n');
+        }
+    }
+    answers
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Status {
+    Pass,
+    Fail,
+    Unknown,
+    Error,
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Status::Pass => "PASS",
+            Status::Fail => "FAIL",
+            Status::Unknown => "UNKNOWN",
+            Status::Error => "ERROR",
+        })
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Format {
+    Text,
+    Json,
+    Csv,
+}
+
+impl Format {
+    fn parse(value: &str) -> Self {
+        value
+            .parse()
+            .unwrap_or_else(|_| panic!("unknown format {value}, expected text, json or csv"))
+    }
+
+    /// Prints what comes before the first result, once per process.
+    pub fn print_header(self) {
+        if self == Format::Csv {
+            CSV_HEADER.call_once(|| println!("{}", Record::CSV_HEADER));
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Result of one operation, every field but the names is `None` when disabled.
+/// Exactly one of `answer` and `error` is set when enabled.
+/// With `--bench` the duration is the median over all runs.
+pub struct Record {
+    pub module_name: &'static str,
+    pub name: &'static str,
+    pub answer: Option<Answer>,
+    pub error: Option<Error>,
+    pub status: Option<Status>,
+    pub duration: Option<Duration>,
+}
+
+impl Record {
+    const CSV_HEADER: &'static str = "module,part,answer,error,enabled,status,duration_ms";
+
+    fn duration_ms(&self) -> Option<String> {
+        self.duration
+            .map(|d| format!("{:.3}", d.as_secs_f64() * 1000.0))
+    }
+
+    /// A single line JSON object, suitable for JSON Lines output.
+    pub fn to_json(&self) -> String {
+        format!(
+            "{{\"module\":{},\"part\":{},\"answer\":{},\"error\":{},\"enabled\":{},\"status\":{},\"duration_ms\":{}}}",
+            json_string(self.module_name),
+            json_string(self.name),
+            self.answer.as_ref().map_or("null".to_string(), json_answer),
+            self.error
+                .as_ref()
+                .map_or("null".to_string(), |e| json_string(&e.to_string())),
+            self.status.is_some(),
+            self.status
+                .map_or("null".to_string(), |s| json_string(&s.to_string())),
+            self.duration_ms().unwrap_or_else(|| "null".to_string())
+        )
+    }
+
+    pub fn to_csv(&self) -> String {
+        [
+            csv_field(self.module_name),
+            csv_field(self.name),
+            self.answer
+                .as_ref()
+                .map_or(String::new(), |a| csv_field(&a.to_string())),
+            self.error
+                .as_ref()
+                .map_or(String::new(), |e| csv_field(&e.to_string())),
+            self.status.is_some().to_string(),
+            self.status.map_or(String::new(), |s| s.to_string()),
+            self.duration_ms().unwrap_or_default(),
+        ]
+        .join(",")
+    }
+}
+
+/// Numbers are written as JSON numbers, grids as arrays of rows and
+/// letters as their text.
+fn json_answer(answer: &Answer) -> String {
+    match answer {
+        Answer::Int(_) | Answer::BigInt(_) => answer.to_string(),
+        Answer::Text(text) | Answer::Letters { text, .. } => json_string(text),
+        Answer::Grid(rows) => {
+            let rows = rows.iter().map(|row| json_string(row)).collect::<Vec<_>>();
+            format!("[{}]", rows.join(","))
+        }
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::from('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+pub struct Timing {
+    pub module_name: &'static str,
+    pub name: &'static str,
+    pub runs: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+}
+
+impl Timing {
+    fn new(module_name: &'static str, name: &'static str, mut durations: Vec<Duration>) -> Self {
+        durations.sort();
+        let runs = durations.len();
+        Self {
+            module_name,
+            name,
+            runs,
+            min: durations[0],
+            median: durations[runs / 2],
+            mean: durations.iter().sum::<Duration>() / runs as u32,
+        }
+    }
+
+    /// Prints one row per timing, followed by the total of the medians.
+    pub fn print_summary(timings: &[Timing]) {
+        println!(
+            "{:<8} {:<8} {:>5} {:>12} {:>12} {:>12}",
+            "module", "part", "runs", "min", "median", "mean"
+        );
+        for t in timings {
+            println!(
+                "{:<8} {:<8} {:>5} {:>12.3?} {:>12.3?} {:>12.3?}",
+                t.module_name, t.name, t.runs, t.min, t.median, t.mean
+            );
+        }
+        let total = timings.iter().map(|t| t.median).sum::<Duration>();
+        println!(
+            "{:<8} {:<8} {:>5} {:>12} {:>12.3?}",
+            "total", "", "", "", total
+        );
+    }
+}
+
+impl Display for Timing {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "min {:.3?} median {:.3?} mean {:.3?} ({} runs)",
+            self.min, self.median, self.mean, self.runs
+        )
+    }
+}
+
+fn read_input(path: &str) -> String {
+    if path == "-" {
+        let mut input = String::new();
+        std::io::stdin()
+            .read_to_string(&mut input)
+            .unwrap_or_else(|e| panic!("failed to read stdin: {e}"));
+        input
+    } else {
+        std::fs::read_to_string(path).unwrap_or_else(|e| panic!("failed to read {path}: {e}"))
     }
 }
 
@@ -57,10 +719,235 @@ macro_rules! verify {
         let result = $op($input.lines());
         assert_eq!(
             result,
-            $expected,
+            Ok($crate::Answer::from($expected)),
             "{} {}",
             module_path!(),
             std::stringify!($op)
         );
     }};
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_from_args_operations() {
+        let runner = Runner::from_args("test", args(&["part2"]));
+        assert_eq!(runner.operations, HashSet::from(["part2".to_string()]));
+        assert_eq!(runner.input, None);
+    }
+
+    #[test]
+    fn test_from_args_input() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/src/bin/day01/example.txt");
+        let expected = include_str!("bin/day01/example.txt");
+        let runner = Runner::from_args("test", args(&["--input", path, "part1"]));
+        assert_eq!(runner.operations, HashSet::from(["part1".to_string()]));
+        assert_eq!(runner.input.as_deref(), Some(expected));
+        let runner = Runner::from_args("test", args(&[&format!("--input={path}")]));
+        assert_eq!(runner.input.as_deref(), Some(expected));
+    }
+
+    #[test]
+    fn test_from_args_bench() {
+        assert_eq!(Runner::from_args("test", args(&[])).bench, None);
+        let runner = Runner::from_args("test", args(&["--bench"]));
+        assert_eq!(runner.bench, Some(DEFAULT_BENCH_RUNS));
+        let runner = Runner::from_args("test", args(&["--bench=3", "part1"]));
+        assert_eq!(runner.bench, Some(3));
+    }
+
+    #[test]
+    fn test_from_args_format() {
+        assert_eq!(Runner::from_args("test", args(&[])).format, Format::Text);
+        let runner = Runner::from_args("test", args(&["--format", "json"]));
+        assert_eq!(runner.format, Format::Json);
+        let runner = Runner::from_args("test", args(&["--format=csv"]));
+        assert_eq!(runner.format, Format::Csv);
+    }
+
+    #[test]
+    fn test_from_args_trace() {
+        assert_eq!(Runner::from_args("test", args(&[])).playback, Playback::Off);
+        let runner = Runner::from_args("test", args(&["--play", "--steps=5"]));
+        assert_eq!(runner.playback, Playback::Animate { fps: DEFAULT_FPS });
+        assert_eq!(runner.steps, Some(5));
+        let runner = Runner::from_args("test", args(&["--fps=30"]));
+        assert_eq!(runner.playback, Playback::Animate { fps: 30 });
+        let runner = Runner::from_args("test", args(&["--trace", "part1"]));
+        assert_eq!(runner.playback, Playback::Trace);
+        assert_eq!(runner.operations, HashSet::from(["part1".to_string()]));
+        runner.known_answers("part1: 1\n");
+        runner.run(
+            &Named::create(|_| Ok(Answer::from(tracing() as i32)), "part1"),
+            "input",
+        );
+        assert_eq!(runner.statuses(), vec![Status::Pass]);
+        assert!(!tracing());
+    }
+
+    #[test]
+    fn test_from_args_images() {
+        let runner = Runner::from_args("test", args(&[]));
+        assert_eq!(
+            (runner.images, runner.image_format),
+            (None, ImageFormat::Png)
+        );
+        let runner = Runner::from_args("test", args(&["--images", "out", "--image-format=ppm"]));
+        assert_eq!(runner.images, Some(PathBuf::from("out")));
+        assert_eq!(runner.image_format, ImageFormat::Ppm);
+    }
+
+    #[test]
+    fn test_state_per_thread() {
+        fn elsewhere() -> bool {
+            std::thread::spawn(|| tracing() || exporting() || parallel())
+                .join()
+                .unwrap()
+        }
+        let runner = Runner::from_args("test", args(&["--trace", "--images", "out"]));
+        runner.known_answers("part1: 1\n");
+        let op: Operation = |_| {
+            Ok(Answer::from(
+                (tracing() && exporting() && !elsewhere()) as i32,
+            ))
+        };
+        runner.run(&Named::create(op, "part1"), "input");
+        assert_eq!(runner.statuses(), vec![Status::Pass]);
+        let runner = Runner::from_args("test", args(&["--parallel"]));
+        runner.known_answers("part1: 1\n");
+        let op: Operation = |_| Ok(Answer::from((parallel() && !elsewhere()) as i32));
+        runner.run(&Named::create(op, "part1"), "input");
+        assert_eq!(runner.statuses(), vec![Status::Pass]);
+        assert!(!tracing() && !exporting() && !parallel());
+    }
+
+    #[test]
+    fn test_snapshot() {
+        struct Dot;
+        impl ToImage for Dot {
+            fn to_image(&self) -> Image {
+                Image::new(1, 1, [255, 255, 255])
+            }
+        }
+        let dir = std::env::temp_dir().join(format!("aoc_snapshot_{}", std::process::id()));
+        let runner = Runner::from_args("day00", args(&["--images", dir.to_str().unwrap()]));
+        let op: Operation = |_| {
+            snapshot(&Dot);
+            snapshot(&Dot);
+            Ok(Answer::from(exporting() as i32))
+        };
+        runner.run(&Named::create(op, "part1"), "input");
+        assert!(!exporting());
+        let saved = std::fs::read(dir.join("day00_part1_0001.png")).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(saved, Dot.to_image().to_png());
+    }
+
+    #[test]
+    fn test_record() {
+        let record = Record {
+            module_name: "day10",
+            name: "part2",
+            answer: Some(Answer::from("#.\"\n.#,\n")),
+            error: None,
+            status: Some(Status::Pass),
+            duration: Some(Duration::from_micros(1500)),
+        };
+        assert_eq!(
+            record.to_json(),
+            r##"{"module":"day10","part":"part2","answer":"#.\"\n.#,\n","error":null,"enabled":true,"status":"PASS","duration_ms":1.500}"##
+        );
+        assert_eq!(
+            record.to_csv(),
+            "day10,part2,\"#.\"\"\n.#,\n\",,true,PASS,1.500"
+        );
+        let disabled = Record {
+            answer: None,
+            status: None,
+            duration: None,
+            ..record
+        };
+        assert_eq!(
+            disabled.to_json(),
+            r#"{"module":"day10","part":"part2","answer":null,"error":null,"enabled":false,"status":null,"duration_ms":null}"#
+        );
+        assert_eq!(disabled.to_csv(), "day10,part2,,,false,,");
+        let grid = Record {
+            answer: Some(Answer::grid("#.\n.#\n")),
+            ..disabled
+        };
+        assert!(grid.to_json().contains(r##""answer":["#.",".#"]"##));
+        let number = Record {
+            answer: Some(Answer::from(13140)),
+            ..grid
+        };
+        assert!(number.to_json().contains(r#""answer":13140,"#));
+    }
+
+    #[test]
+    fn test_parse_answers() {
+        let answers = parse_answers("part1: 13140\npart2:\n##..\n.##.\n");
+        assert_eq!(answers["part1"], "13140");
+        assert_eq!(answers["part2"], "##..\n.##.\n");
+        assert_eq!(answers.len(), 2);
+    }
+
+    #[test]
+    fn test_known_answers() {
+        let runner = Runner::from_args("test", args(&[]));
+        runner.known_answers("part1: 1\n");
+        runner.run(&Named::create(|_| Ok(Answer::from(1)), "part1"), "input");
+        runner.run(&Named::create(|_| Ok(Answer::from(2)), "part1"), "input");
+        runner.run(&Named::create(|_| Ok(Answer::from(3)), "part2"), "input");
+        assert_eq!(
+            runner.statuses(),
+            vec![Status::Pass, Status::Fail, Status::Unknown]
+        );
+        // parts of a day without its input are skipped
+        runner.run(&Named::create(|_| Ok(Answer::from(1)), "part1"), "");
+        assert_eq!(runner.statuses().len(), 3);
+    }
+
+    #[test]
+    fn test_errors() {
+        let runner = Runner::from_args("test", args(&[]));
+        runner.known_answers("part1: 1\n");
+        let error: Named<Operation> =
+            Named::create(|_| Err(Error::parse(3, "expected a number")), "part1");
+        runner.run(&error, "input");
+        runner.run(&Named::create(|_| panic!("oops"), "part2"), "input");
+        runner.run(&Named::create(|_| Ok(Answer::from(1)), "part1"), "input");
+        assert_eq!(
+            runner.statuses(),
+            vec![Status::Error, Status::Error, Status::Pass]
+        );
+        let error = Error::parse(3, "expected a number");
+        assert_eq!(
+            error.to_string(),
+            "parse error on line 3: expected a number"
+        );
+        assert_eq!(
+            Error::parse_at(3, 7, "expected a number").to_string(),
+            "parse error on line 3, column 7: expected a number"
+        );
+        assert_eq!(error.offset_lines(2), Error::parse(5, "expected a number"));
+        let panics: Named<Operation> = Named::create(|_| panic!("oops"), "part2");
+        assert_eq!(call(&panics, ""), Err(Error::Panic("oops".to_string())));
+    }
+
+    #[test]
+    fn test_timing() {
+        let millis = [4, 1, 3, 2, 10].map(Duration::from_millis).to_vec();
+        let timing = Timing::new("test", "part1", millis);
+        assert_eq!(timing.runs, 5);
+        assert_eq!(timing.min, Duration::from_millis(1));
+        assert_eq!(timing.median, Duration::from_millis(3));
+        assert_eq!(timing.mean, Duration::from_millis(4));
+    }
+}
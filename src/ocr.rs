@@ -0,0 +1,95 @@
+/// Letters of the font used by puzzles which draw text, each 4 pixels wide
+/// and 6 high, with one blank column between letters. Not every letter is
+/// known, these are the ones seen in puzzles so far.
+const FONT: [(char, [&str; 6]); 17] = [
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', [".###", "..#.", "..#.", "..#.", "..#.", ".###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+const HEIGHT: usize = 6;
+const WIDTH: usize = 4;
+
+/// Reads the text in a picture drawn with `#` for lit pixels and any other
+/// char for dark ones. `None` unless the picture is 6 rows high, every
+/// letter in it is known and the columns between letters are dark.
+pub fn ocr<S: AsRef<str>>(rows: &[S]) -> Option<String> {
+    if rows.len() != HEIGHT {
+        return None;
+    }
+    let rows = rows
+        .iter()
+        .map(|row| {
+            row.as_ref()
+                .trim_end()
+                .chars()
+                .map(|c| c == '#')
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let lit = |y: usize, x: usize| rows[y].get(x).copied().unwrap_or(false);
+    let letters = (0..width)
+        .step_by(WIDTH + 1)
+        .map(|left| {
+            FONT.iter()
+                .find(|(_, glyph)| {
+                    glyph.iter().enumerate().all(|(y, row)| {
+                        row.chars()
+                            .enumerate()
+                            .all(|(x, c)| (c == '#') == lit(y, left + x))
+                    })
+                })
+                .filter(|_| (0..HEIGHT).all(|y| !lit(y, left + WIDTH)))
+                .map(|&(letter, _)| letter)
+        })
+        .collect::<Option<String>>()?;
+    (!letters.is_empty()).then_some(letters)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ocr() {
+        let picture = [
+            "#..#.####.#....#.....##.",
+            "#..#.#....#....#....#..#",
+            "####.###..#....#....#..#",
+            "#..#.#....#....#....#..#",
+            "#..#.#....#....#....#..#",
+            "#..#.####.####.####..##.",
+        ];
+        assert_eq!(ocr(&picture), Some("HELLO".to_string()));
+        let unknown = picture.map(|row| row.replace("#....#", "#..#.#"));
+        assert_eq!(ocr(&unknown), None);
+        let mut joined = picture.map(String::from);
+        joined[2].replace_range(4..5, "#");
+        assert_eq!(ocr(&joined), None);
+        assert_eq!(ocr(&picture[..5]), None);
+        assert_eq!(ocr(&[""; 6]), None);
+    }
+
+    #[test]
+    fn test_font() {
+        for (letter, glyph) in FONT {
+            assert!(glyph.iter().all(|row| row.len() == WIDTH), "{letter}");
+            assert_eq!(ocr(&glyph), Some(letter.to_string()));
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use std::{
+    cell::Cell,
+    ops::Range,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
+
+// kept per thread, so that runners working at once don't switch it for
+// each other
+thread_local! {
+    static PARALLEL: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Whether `par_map` and `par_find_map` called on this thread use several
+/// threads, which `Runner` turns on for `--parallel` while a part runs.
+/// Otherwise they work like their iterator counterparts, which keeps timings
+/// and debugging output simple.
+pub fn parallel() -> bool {
+    PARALLEL.get()
+}
+
+pub(crate) fn set_parallel(on: bool) {
+    PARALLEL.set(on);
+}
+
+fn threads() -> usize {
+    thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+/// `f` of every item, in the order of `items`, however the work is spread
+/// over threads.
+pub fn par_map<T: Sync, U: Send>(items: &[T], f: impl Fn(&T) -> U + Sync) -> Vec<U> {
+    let threads = if parallel() { threads() } else { 1 };
+    map_on(threads, items, f)
+}
+
+fn map_on<T: Sync, U: Send>(threads: usize, items: &[T], f: impl Fn(&T) -> U + Sync) -> Vec<U> {
+    let threads = threads.min(items.len());
+    if threads <= 1 {
+        return items.iter().map(f).collect();
+    }
+    // items are handed out one at a time, since they may take very different
+    // amounts of work, and the results put back in order afterwards
+    let next = AtomicUsize::new(0);
+    let mut results = thread::scope(|scope| {
+        let workers = (0..threads)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut results = Vec::new();
+                    loop {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        match items.get(i) {
+                            Some(item) => results.push((i, f(item))),
+                            None => return results,
+                        }
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        workers
+            .into_iter()
+            .flat_map(|worker| {
+                worker
+                    .join()
+                    .unwrap_or_else(|e| std::panic::resume_unwind(e))
+            })
+            .collect::<Vec<_>>()
+    });
+    results.sort_unstable_by_key(|&(i, _)| i);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+/// How many indices `par_find_map` hands out at a time, so that cheap checks
+/// aren't dominated by threads competing for the next index.
+const CHUNK: usize = 256;
+
+/// The first `Some` from `f` over `range`, like `range.find_map(f)`. Threads
+/// stop once every index before a result has been checked, so the same
+/// result is found however the work is spread.
+pub fn par_find_map<U: Send>(
+    range: Range<usize>,
+    f: impl Fn(usize) -> Option<U> + Sync,
+) -> Option<U> {
+    let threads = if parallel() { threads() } else { 1 };
+    find_map_on(threads, range, f)
+}
+
+fn find_map_on<U: Send>(
+    threads: usize,
+    range: Range<usize>,
+    f: impl Fn(usize) -> Option<U> + Sync,
+) -> Option<U> {
+    let threads = threads.min(range.len().div_ceil(CHUNK));
+    if threads <= 1 {
+        return range.into_iter().find_map(f);
+    }
+    let next = AtomicUsize::new(range.start);
+    let found = AtomicUsize::new(usize::MAX);
+    thread::scope(|scope| {
+        let workers = (0..threads)
+            .map(|_| {
+                scope.spawn(|| loop {
+                    let start = next.fetch_add(CHUNK, Ordering::Relaxed);
+                    if start >= range.end || start >= found.load(Ordering::Relaxed) {
+                        return None;
+                    }
+                    let end = (start + CHUNK).min(range.end);
+                    for i in start..end {
+                        if i >= found.load(Ordering::Relaxed) {
+                            return None;
+                        }
+                        if let Some(result) = f(i) {
+                            found.fetch_min(i, Ordering::Relaxed);
+                            return Some((i, result));
+                        }
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        workers
+            .into_iter()
+            .filter_map(|worker| {
+                worker
+                    .join()
+                    .unwrap_or_else(|e| std::panic::resume_unwind(e))
+            })
+            .min_by_key(|&(i, _)| i)
+            .map(|(_, result)| result)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_par_map() {
+        let items = (0..1000).collect::<Vec<u64>>();
+        let squares = items.iter().map(|&x| x * x).collect::<Vec<_>>();
+        for threads in [1, 4] {
+            assert_eq!(map_on(threads, &items, |&x| x * x), squares);
+            assert_eq!(map_on(threads, &[] as &[u64], |&x| x), vec![]);
+        }
+        assert_eq!(par_map(&items, |&x| x * x), squares);
+    }
+
+    #[test]
+    fn test_par_find_map() {
+        let multiple = |i: usize| (i > 0 && i.is_multiple_of(997)).then_some(i);
+        for threads in [1, 4] {
+            assert_eq!(find_map_on(threads, 0..100_000, multiple), Some(997));
+            assert_eq!(find_map_on(threads, 998..1994, multiple), None);
+            assert_eq!(find_map_on(threads, 998..1995, multiple), Some(1994));
+            assert_eq!(find_map_on(threads, 5..5, multiple), None);
+        }
+        assert_eq!(par_find_map(0..100_000, multiple), Some(997));
+    }
+}
@@ -0,0 +1,278 @@
+use crate::{Error, ParsePosError, Result};
+use lazy_regex::{regex, Captures, Regex};
+use std::{fmt::Display, str::FromStr};
+
+/// A problem found while parsing a single line, which becomes an
+/// `Error::Parse` once the line number is known.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct LineError {
+    /// Counts chars from 1, when the problem is at a particular place.
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+impl LineError {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            column: None,
+            message: message.into(),
+        }
+    }
+
+    pub fn at(column: usize, message: impl Into<String>) -> Self {
+        Self {
+            column: Some(column),
+            message: message.into(),
+        }
+    }
+
+    pub fn on_line(self, line: usize) -> Error {
+        Error::Parse {
+            line,
+            column: self.column,
+            message: self.message,
+        }
+    }
+}
+
+impl Display for LineError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.column {
+            Some(column) => write!(f, "column {column}: {}", self.message),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+impl From<String> for LineError {
+    fn from(message: String) -> Self {
+        Self::new(message)
+    }
+}
+
+impl From<&str> for LineError {
+    fn from(message: &str) -> Self {
+        Self::new(message)
+    }
+}
+
+impl From<ParsePosError> for LineError {
+    fn from(error: ParsePosError) -> Self {
+        Self::new(error.to_string())
+    }
+}
+
+/// The column of byte offset `offset` within `text`, counting chars from 1.
+fn column(text: &str, offset: usize) -> usize {
+    text[..offset].chars().count() + 1
+}
+
+/// Parses every line with `parse`, numbering the lines from 1 in errors.
+pub fn parse_lines<'a, T>(
+    input: impl IntoIterator<Item = &'a str>,
+    parse: impl FnMut(&'a str) -> Result<T, LineError>,
+) -> Result<Vec<T>> {
+    parse_numbered(1, input, parse)
+}
+
+fn parse_numbered<'a, T>(
+    first_line: usize,
+    input: impl IntoIterator<Item = &'a str>,
+    mut parse: impl FnMut(&'a str) -> Result<T, LineError>,
+) -> Result<Vec<T>> {
+    input
+        .into_iter()
+        .enumerate()
+        .map(|(index, line)| parse(line).map_err(|error| error.on_line(first_line + index)))
+        .collect()
+}
+
+/// A run of non-blank lines, as split up by `sections`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Section<'a> {
+    /// The line number of the first line in the whole input, from 1.
+    pub first_line: usize,
+    pub lines: Vec<&'a str>,
+}
+
+impl<'a> Section<'a> {
+    /// Parses every line of the section, numbering errors within the input.
+    pub fn parse_lines<T>(
+        &self,
+        parse: impl FnMut(&'a str) -> Result<T, LineError>,
+    ) -> Result<Vec<T>> {
+        parse_numbered(self.first_line, self.lines.iter().copied(), parse)
+    }
+
+    /// Parses the lines joined by newlines as one text, for patterns which
+    /// span several lines. Errors are reported on the first line.
+    pub fn parse_text<T>(&self, parse: impl FnOnce(&str) -> Result<T, LineError>) -> Result<T> {
+        parse(&self.lines.join("\n")).map_err(|error| error.on_line(self.first_line))
+    }
+}
+
+/// Splits the input into sections separated by blank lines. Runs of several
+/// blank lines separate just like one, and never produce empty sections.
+pub fn sections<'a>(input: impl IntoIterator<Item = &'a str>) -> Vec<Section<'a>> {
+    let mut sections: Vec<Section> = Vec::new();
+    let mut in_section = false;
+    for (index, line) in input.into_iter().enumerate() {
+        if line.trim().is_empty() {
+            in_section = false;
+        } else if in_section {
+            sections.last_mut().unwrap().lines.push(line);
+        } else {
+            in_section = true;
+            sections.push(Section {
+                first_line: index + 1,
+                lines: vec![line],
+            });
+        }
+    }
+    sections
+}
+
+/// Every integer in `text`, ignoring whatever surrounds them. A `-` right
+/// before the digits makes the integer negative, so `1-2` gives 1 and -2.
+pub fn integers<T: FromStr>(text: &str) -> Result<Vec<T>, LineError> {
+    regex!(r"-?\d+")
+        .find_iter(text)
+        .map(|m| {
+            m.as_str().parse::<T>().map_err(|_| {
+                LineError::at(
+                    column(text, m.start()),
+                    format!("integer {:?} is out of range", m.as_str()),
+                )
+            })
+        })
+        .collect()
+}
+
+/// Types which can be built from the groups of a regex match.
+pub trait FromCaptures: Sized {
+    fn from_captures(text: &str, captures: &Captures) -> Result<Self, LineError>;
+}
+
+fn capture<T: FromStr>(text: &str, captures: &Captures, group: usize) -> Result<T, LineError> {
+    let m = captures
+        .get(group)
+        .ok_or_else(|| LineError::new(format!("group {group} did not match")))?;
+    m.as_str().parse::<T>().map_err(|_| {
+        LineError::at(
+            column(text, m.start()),
+            format!("unexpected {:?} in group {group}", m.as_str()),
+        )
+    })
+}
+
+macro_rules! impl_from_captures {
+    ($($ty:ident $group:literal),+) => {
+        /// Parses each group, in order, into the matching tuple element.
+        impl<$($ty: FromStr),+> FromCaptures for ($($ty,)+) {
+            fn from_captures(text: &str, captures: &Captures) -> Result<Self, LineError> {
+                Ok(($(capture::<$ty>(text, captures, $group)?,)+))
+            }
+        }
+    };
+}
+
+impl_from_captures!(A 1);
+impl_from_captures!(A 1, B 2);
+impl_from_captures!(A 1, B 2, C 3);
+impl_from_captures!(A 1, B 2, C 3, D 4);
+impl_from_captures!(A 1, B 2, C 3, D 4, E 5);
+impl_from_captures!(A 1, B 2, C 3, D 4, E 5, F 6);
+impl_from_captures!(A 1, B 2, C 3, D 4, E 5, F 6, G 7);
+impl_from_captures!(A 1, B 2, C 3, D 4, E 5, F 6, G 7, H 8);
+
+/// Matches `regex` against `text` and parses its groups, for example
+/// `captures::<(String, usize)>(regex!(r"(\w+)=(\d+)"), line)`.
+pub fn captures<T: FromCaptures>(regex: &Regex, text: &str) -> Result<T, LineError> {
+    let captures = regex
+        .captures(text)
+        .ok_or_else(|| LineError::new(format!("expected `{}`, got {text:?}", regex.as_str())))?;
+    T::from_captures(text, &captures)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_lines() {
+        let parse = |line: &str| {
+            line.parse::<u8>()
+                .map_err(|_| LineError::new("not a number"))
+        };
+        assert_eq!(parse_lines("1\n2".lines(), parse), Ok(vec![1, 2]));
+        assert_eq!(
+            parse_lines("1\n2\nx".lines(), parse),
+            Err(Error::parse(3, "not a number"))
+        );
+    }
+
+    #[test]
+    fn test_sections() {
+        let input = "a\nb\n\n\nc\n  \nd\n";
+        let sections = sections(input.lines());
+        assert_eq!(
+            sections,
+            vec![
+                Section {
+                    first_line: 1,
+                    lines: vec!["a", "b"]
+                },
+                Section {
+                    first_line: 5,
+                    lines: vec!["c"]
+                },
+                Section {
+                    first_line: 7,
+                    lines: vec!["d"]
+                },
+            ]
+        );
+        assert_eq!(
+            sections[0].parse_lines(|line| match line {
+                "a" => Ok(1),
+                _ => Err(LineError::at(1, "expected a")),
+            }),
+            Err(Error::parse_at(2, 1, "expected a"))
+        );
+        assert_eq!(
+            sections[0].parse_text(|text| Ok(text.to_string())),
+            Ok("a\nb".to_string())
+        );
+    }
+
+    #[test]
+    fn test_integers() {
+        assert_eq!(integers::<i32>("x=2, y=-18: 1-2"), Ok(vec![2, -18, 1, -2]));
+        assert_eq!(integers::<u8>("none"), Ok(vec![]));
+        assert_eq!(
+            integers::<u8>("é 12 300"),
+            Err(LineError::at(6, "integer \"300\" is out of range"))
+        );
+    }
+
+    #[test]
+    fn test_captures() {
+        let regex = regex!(r"move (\d+) from (\w+)");
+        assert_eq!(
+            captures::<(usize, String)>(regex, "move 3 from a1"),
+            Ok((3, "a1".to_string()))
+        );
+        assert_eq!(
+            captures::<(u8, String)>(regex, "move 300 from a1"),
+            Err(LineError::at(6, "unexpected \"300\" in group 1"))
+        );
+        assert_eq!(
+            captures::<(usize,)>(regex, "stay").map_err(|e| e.on_line(4)),
+            Err(Error::parse(
+                4,
+                r#"expected `move (\d+) from (\w+)`, got "stay""#
+            ))
+        );
+        assert_eq!(LineError::at(6, "bad").to_string(), "column 6: bad");
+    }
+}
@@ -0,0 +1,261 @@
+use std::{
+    fmt::Display,
+    ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign},
+    str::FromStr,
+};
+
+/// A 2D point or offset. In grids `y` counts rows down from the top.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default, Debug)]
+pub struct Pos<T = isize> {
+    pub x: T,
+    pub y: T,
+}
+
+/// A 3D point or offset.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default, Debug)]
+pub struct Pos3<T = isize> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T> Pos<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+}
+
+impl<T> Pos3<T> {
+    pub const fn new(x: T, y: T, z: T) -> Self {
+        Self { x, y, z }
+    }
+}
+
+impl Pos {
+    pub const RIGHT: Self = Self::new(1, 0);
+    pub const DOWN: Self = Self::new(0, 1);
+    pub const LEFT: Self = Self::new(-1, 0);
+    pub const UP: Self = Self::new(0, -1);
+
+    /// Offsets to the positions which share an edge, clockwise from `RIGHT`.
+    pub const NEIGHBORS4: [Self; 4] = [Self::RIGHT, Self::DOWN, Self::LEFT, Self::UP];
+
+    /// Offsets to the positions which share an edge or a corner, row by row.
+    pub const NEIGHBORS8: [Self; 8] = [
+        Self::new(-1, -1),
+        Self::new(0, -1),
+        Self::new(1, -1),
+        Self::new(-1, 0),
+        Self::new(1, 0),
+        Self::new(-1, 1),
+        Self::new(0, 1),
+        Self::new(1, 1),
+    ];
+}
+
+impl Pos3 {
+    /// Offsets to the positions which share a face.
+    pub const NEIGHBORS6: [Self; 6] = [
+        Self::new(-1, 0, 0),
+        Self::new(1, 0, 0),
+        Self::new(0, -1, 0),
+        Self::new(0, 1, 0),
+        Self::new(0, 0, -1),
+        Self::new(0, 0, 1),
+    ];
+}
+
+impl<T: Copy + Neg<Output = T>> Pos<T> {
+    /// Rotates by 90°, clockwise when `y` counts down.
+    pub fn rotate_right(self) -> Self {
+        Self::new(-self.y, self.x)
+    }
+
+    /// Rotates by 90°, counterclockwise when `y` counts down.
+    pub fn rotate_left(self) -> Self {
+        Self::new(self.y, -self.x)
+    }
+}
+
+fn abs_diff<T: Ord + Sub<Output = T>>(a: T, b: T) -> T {
+    if a > b {
+        a - b
+    } else {
+        b - a
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ParsePosError(String);
+
+impl Display for ParsePosError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for ParsePosError {}
+
+/// Implements the operations shared by `Pos` and `Pos3` over their fields.
+macro_rules! impl_pos {
+    ($name:ident, $format:literal, $($field:ident),+) => {
+        impl<T> $name<T> {
+            /// Applies `f` to every coordinate.
+            pub fn map<U>(self, mut f: impl FnMut(T) -> U) -> $name<U> {
+                $name { $($field: f(self.$field)),+ }
+            }
+
+            /// Converts every coordinate, if all of them fit in `U`.
+            pub fn cast<U: TryFrom<T>>(self) -> Option<$name<U>> {
+                Some($name { $($field: U::try_from(self.$field).ok()?),+ })
+            }
+        }
+
+        impl<T: Copy + Ord + Add<Output = T> + Sub<Output = T>> $name<T> {
+            pub fn manhattan(self, other: Self) -> T {
+                [$(abs_diff(self.$field, other.$field)),+]
+                    .into_iter()
+                    .reduce(|a, b| a + b)
+                    .unwrap()
+            }
+
+            pub fn chebyshev(self, other: Self) -> T {
+                [$(abs_diff(self.$field, other.$field)),+]
+                    .into_iter()
+                    .max()
+                    .unwrap()
+            }
+        }
+
+        impl<T: Add<Output = T>> Add for $name<T> {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self {
+                Self { $($field: self.$field + rhs.$field),+ }
+            }
+        }
+
+        impl<T: Sub<Output = T>> Sub for $name<T> {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self {
+                Self { $($field: self.$field - rhs.$field),+ }
+            }
+        }
+
+        impl<T: Copy + Mul<Output = T>> Mul<T> for $name<T> {
+            type Output = Self;
+
+            fn mul(self, rhs: T) -> Self {
+                Self { $($field: self.$field * rhs),+ }
+            }
+        }
+
+        impl<T: Neg<Output = T>> Neg for $name<T> {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                Self { $($field: -self.$field),+ }
+            }
+        }
+
+        impl<T: AddAssign> AddAssign for $name<T> {
+            fn add_assign(&mut self, rhs: Self) {
+                $(self.$field += rhs.$field;)+
+            }
+        }
+
+        impl<T: SubAssign> SubAssign for $name<T> {
+            fn sub_assign(&mut self, rhs: Self) {
+                $(self.$field -= rhs.$field;)+
+            }
+        }
+
+        /// Formats as the comma separated coordinates, as accepted by `FromStr`.
+        impl<T: Display> Display for $name<T> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                let coordinates = [$(self.$field.to_string()),+];
+                f.write_str(&coordinates.join(","))
+            }
+        }
+
+        /// Parses comma separated coordinates, ignoring whitespace around each.
+        impl<T: FromStr> FromStr for $name<T> {
+            type Err = ParsePosError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                let error = || ParsePosError(format!("expected `{}`, got {s:?}", $format));
+                let mut coordinates = s.split(',').map(|c| c.trim().parse::<T>());
+                let pos = Self {
+                    $($field: coordinates.next().ok_or_else(error)?.map_err(|_| error())?),+
+                };
+                match coordinates.next() {
+                    Some(_) => Err(error()),
+                    None => Ok(pos),
+                }
+            }
+        }
+    };
+}
+
+impl_pos!(Pos, "x,y", x, y);
+impl_pos!(Pos3, "x,y,z", x, y, z);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        let a = Pos::new(1, 2);
+        let b = Pos::new(4, -2);
+        assert_eq!(a + b, Pos::new(5, 0));
+        assert_eq!(a - b, Pos::new(-3, 4));
+        assert_eq!(b * 2, Pos::new(8, -4));
+        assert_eq!(-a, Pos::new(-1, -2));
+        let mut c = a;
+        c += b;
+        c -= Pos::new(1, 1);
+        assert_eq!(c, Pos::new(4, -1));
+        assert_eq!(Pos3::new(1, 2, 3) + Pos3::NEIGHBORS6[5], Pos3::new(1, 2, 4));
+    }
+
+    #[test]
+    fn test_distance() {
+        let a = Pos::new(1, 2);
+        let b = Pos::new(4, -2);
+        assert_eq!(a.manhattan(b), 7);
+        assert_eq!(a.chebyshev(b), 4);
+        assert_eq!(Pos::<usize>::new(5, 1).manhattan(Pos::new(2, 3)), 5);
+        assert_eq!(Pos3::new(0, 0, 0).manhattan(Pos3::new(1, -2, 3)), 6);
+        assert_eq!(Pos3::new(0, 0, 0).chebyshev(Pos3::new(1, -2, 3)), 3);
+    }
+
+    #[test]
+    fn test_rotate() {
+        assert_eq!(Pos::RIGHT.rotate_right(), Pos::DOWN);
+        assert_eq!(Pos::DOWN.rotate_right(), Pos::LEFT);
+        assert_eq!(Pos::RIGHT.rotate_left(), Pos::UP);
+        assert_eq!(Pos::new(2, 1).rotate_left().rotate_right(), Pos::new(2, 1));
+    }
+
+    #[test]
+    fn test_cast() {
+        assert_eq!(Pos::new(1, 2).cast::<usize>(), Some(Pos::new(1, 2)));
+        assert_eq!(Pos::new(1, -2).cast::<usize>(), None);
+        assert_eq!(Pos::new(1, 2).map(|c| c * 10), Pos::new(10, 20));
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("3,-4".parse(), Ok(Pos::new(3, -4)));
+        assert_eq!(" 1, 2 ,3".parse(), Ok(Pos3::new(1, 2, 3)));
+        assert_eq!(Pos3::new(1, 2, 3).to_string(), "1,2,3");
+        assert_eq!(
+            "1,2,3".parse::<Pos>(),
+            Err(ParsePosError("expected `x,y`, got \"1,2,3\"".to_string()))
+        );
+        assert!("1,x,3".parse::<Pos3>().is_err());
+        assert!("1,2".parse::<Pos3>().is_err());
+    }
+}
@@ -0,0 +1,90 @@
+use std::{cell::RefCell, fmt::Display, io::Write, time::Duration};
+
+/// Types which can be drawn as text, to follow a simulation while debugging.
+pub trait Render {
+    /// The rows of the drawing, top first.
+    fn render(&self) -> Vec<String>;
+}
+
+/// How `trace` shows frames, chosen by `Runner` options.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum Playback {
+    Off,
+    /// Prints every frame below the previous one, for `--trace`.
+    Trace,
+    /// Redraws the terminal with each frame, `fps` times a second, for `--play`.
+    Animate {
+        fps: u32,
+    },
+}
+
+struct Player {
+    playback: Playback,
+    /// The most frames to show, after which tracing stops.
+    steps: Option<usize>,
+    shown: usize,
+}
+
+impl Player {
+    const OFF: Player = Player {
+        playback: Playback::Off,
+        steps: None,
+        shown: 0,
+    };
+
+    fn showing(&self) -> bool {
+        self.playback != Playback::Off && self.steps.is_none_or(|steps| self.shown < steps)
+    }
+
+    /// Counts the next frame and how to show it, unless no more are shown.
+    fn next_frame(&mut self) -> Option<Playback> {
+        let showing = self.showing();
+        self.shown += showing as usize;
+        showing.then_some(self.playback)
+    }
+}
+
+// kept per thread, so that runners working at once each trace their own part
+thread_local! {
+    static PLAYER: RefCell<Player> = const { RefCell::new(Player::OFF) };
+}
+
+/// Whether `trace` shows frames on this thread, which `Runner` turns on for
+/// `--trace` and `--play` while a part runs. Worth checking before building
+/// an expensive frame or title.
+pub fn tracing() -> bool {
+    PLAYER.with_borrow(Player::showing)
+}
+
+/// Shows the frames traced from now on with `playback`, at most `steps` of them.
+pub(crate) fn start(playback: Playback, steps: Option<usize>) {
+    PLAYER.set(Player {
+        playback,
+        steps,
+        shown: 0,
+    });
+}
+
+pub(crate) fn stop() {
+    PLAYER.set(Player::OFF);
+}
+
+/// Shows `title` followed by the rows of `frame` on stderr when tracing.
+pub fn trace(title: impl Display, frame: &impl Render) {
+    let Some(playback) = PLAYER.with_borrow_mut(Player::next_frame) else {
+        return;
+    };
+    let mut stderr = std::io::stderr().lock();
+    if let Playback::Animate { .. } = playback {
+        // move to the top left corner and clear the screen
+        let _ = write!(stderr, "\x1b[H\x1b[2J");
+    }
+    let _ = writeln!(stderr, "{title}");
+    for row in frame.render() {
+        let _ = writeln!(stderr, "{row}");
+    }
+    let _ = stderr.flush();
+    if let Playback::Animate { fps } = playback {
+        std::thread::sleep(Duration::from_secs_f64(1.0 / fps as f64));
+    }
+}
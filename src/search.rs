@@ -0,0 +1,263 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, VecDeque},
+    hash::Hash,
+};
+
+/// Distances from the start nodes of a search to every node it reached,
+/// along with the predecessor of each node on a shortest path.
+#[derive(Clone, Debug)]
+pub struct Search<N> {
+    distances: HashMap<N, usize>,
+    parents: HashMap<N, N>,
+}
+
+impl<N: Clone + Eq + Hash> Search<N> {
+    fn new() -> Self {
+        Self {
+            distances: HashMap::new(),
+            parents: HashMap::new(),
+        }
+    }
+
+    pub fn distance(&self, node: &N) -> Option<usize> {
+        self.distances.get(node).copied()
+    }
+
+    /// Every reached node with its distance, in no particular order.
+    pub fn distances(&self) -> impl Iterator<Item = (&N, usize)> + '_ {
+        self.distances
+            .iter()
+            .map(|(node, &distance)| (node, distance))
+    }
+
+    /// A shortest path from one of the start nodes to `to`, both included.
+    pub fn path(&self, to: &N) -> Option<Vec<N>> {
+        self.distances.get(to)?;
+        let mut path = vec![to.clone()];
+        while let Some(parent) = self.parents.get(path.last().unwrap()) {
+            path.push(parent.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// Breadth first search where every edge has length 1.
+pub fn bfs<N, I>(start: N, neighbors: impl FnMut(&N) -> I) -> Search<N>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    bfs_multi([start], neighbors)
+}
+
+/// Breadth first search from several start nodes at once, so each distance
+/// is to the nearest start.
+pub fn bfs_multi<N, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut neighbors: impl FnMut(&N) -> I,
+) -> Search<N>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut search = Search::new();
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if search.distances.insert(start.clone(), 0).is_none() {
+            queue.push_back(start);
+        }
+    }
+    while let Some(node) = queue.pop_front() {
+        let distance = search.distances[&node] + 1;
+        for next in neighbors(&node) {
+            if !search.distances.contains_key(&next) {
+                search.distances.insert(next.clone(), distance);
+                search.parents.insert(next.clone(), node.clone());
+                queue.push_back(next);
+            }
+        }
+    }
+    search
+}
+
+/// Shortest paths where `neighbors` gives each adjacent node with the
+/// length of the edge to it.
+pub fn dijkstra<N, I>(start: N, neighbors: impl FnMut(&N) -> I) -> Search<N>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = (N, usize)>,
+{
+    best_first(start, neighbors, |_| 0, |_| false).0
+}
+
+/// Length and nodes of a shortest path from `start` to a node satisfying
+/// `goal`. `heuristic` must never overestimate the remaining distance.
+pub fn astar<N, I>(
+    start: N,
+    neighbors: impl FnMut(&N) -> I,
+    heuristic: impl FnMut(&N) -> usize,
+    goal: impl FnMut(&N) -> bool,
+) -> Option<(usize, Vec<N>)>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = (N, usize)>,
+{
+    let (search, end) = best_first(start, neighbors, heuristic, goal);
+    let end = end?;
+    Some((search.distance(&end)?, search.path(&end)?))
+}
+
+/// Dijkstra's algorithm, or A* given a `heuristic`, stopping at the first
+/// settled node satisfying `goal`. Distances of nodes still queued at that
+/// point may not be the shortest yet.
+fn best_first<N, I>(
+    start: N,
+    mut neighbors: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> usize,
+    mut goal: impl FnMut(&N) -> bool,
+) -> (Search<N>, Option<N>)
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = (N, usize)>,
+{
+    let mut search = Search::new();
+    // the queue refers to nodes by index so that `N` needs no ordering
+    let mut nodes = Vec::new();
+    let mut queue = BinaryHeap::new();
+    search.distances.insert(start.clone(), 0);
+    queue.push(Reverse((heuristic(&start), 0, 0)));
+    nodes.push(start);
+    while let Some(Reverse((_, distance, index))) = queue.pop() {
+        let node = nodes[index].clone();
+        if distance > search.distances[&node] {
+            continue;
+        }
+        if goal(&node) {
+            return (search, Some(node));
+        }
+        for (next, length) in neighbors(&node) {
+            let next_distance = distance + length;
+            if search
+                .distances
+                .get(&next)
+                .is_none_or(|&known| next_distance < known)
+            {
+                search.distances.insert(next.clone(), next_distance);
+                search.parents.insert(next.clone(), node.clone());
+                queue.push(Reverse((
+                    next_distance + heuristic(&next),
+                    next_distance,
+                    nodes.len(),
+                )));
+                nodes.push(next);
+            }
+        }
+    }
+    (search, None)
+}
+
+/// Shortest paths between every pair of nodes, from one search per node.
+#[derive(Clone, Debug)]
+pub struct AllPairs<N> {
+    searches: HashMap<N, Search<N>>,
+}
+
+impl<N: Clone + Eq + Hash> AllPairs<N> {
+    pub fn distance(&self, from: &N, to: &N) -> Option<usize> {
+        self.searches.get(from)?.distance(to)
+    }
+
+    pub fn path(&self, from: &N, to: &N) -> Option<Vec<N>> {
+        self.searches.get(from)?.path(to)
+    }
+
+    /// The search from `from`, if it is one of the nodes.
+    pub fn from(&self, from: &N) -> Option<&Search<N>> {
+        self.searches.get(from)
+    }
+}
+
+/// Runs `dijkstra` from each of `nodes`.
+pub fn all_pairs<N, I>(
+    nodes: impl IntoIterator<Item = N>,
+    mut neighbors: impl FnMut(&N) -> I,
+) -> AllPairs<N>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = (N, usize)>,
+{
+    let searches = nodes
+        .into_iter()
+        .map(|node| (node.clone(), dijkstra(node, &mut neighbors)))
+        .collect();
+    AllPairs { searches }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// a - b - c - d, with a shortcut a - c of length 3.
+    fn weighted(node: &char) -> Vec<(char, usize)> {
+        match node {
+            'a' => vec![('b', 1), ('c', 3)],
+            'b' => vec![('a', 1), ('c', 1)],
+            'c' => vec![('a', 3), ('b', 1), ('d', 5)],
+            'd' => vec![('c', 5)],
+            _ => vec![],
+        }
+    }
+
+    fn unweighted(node: &char) -> Vec<char> {
+        weighted(node).into_iter().map(|(next, _)| next).collect()
+    }
+
+    #[test]
+    fn test_bfs() {
+        let search = bfs('a', unweighted);
+        assert_eq!(search.distance(&'c'), Some(1));
+        assert_eq!(search.distance(&'d'), Some(2));
+        assert_eq!(search.distance(&'x'), None);
+        assert_eq!(search.path(&'d'), Some(vec!['a', 'c', 'd']));
+        assert_eq!(search.path(&'a'), Some(vec!['a']));
+        assert_eq!(search.distances().count(), 4);
+    }
+
+    #[test]
+    fn test_bfs_multi() {
+        let search = bfs_multi(['a', 'd'], unweighted);
+        assert_eq!(search.distance(&'a'), Some(0));
+        assert_eq!(search.distance(&'c'), Some(1));
+        assert_eq!(search.path(&'c'), Some(vec!['a', 'c']));
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        let search = dijkstra('a', weighted);
+        assert_eq!(search.distance(&'c'), Some(2));
+        assert_eq!(search.distance(&'d'), Some(7));
+        assert_eq!(search.path(&'d'), Some(vec!['a', 'b', 'c', 'd']));
+    }
+
+    #[test]
+    fn test_astar() {
+        let heuristic = |node: &char| (b'd' - *node as u8) as usize;
+        assert_eq!(
+            astar('a', weighted, heuristic, |&node| node == 'd'),
+            Some((7, vec!['a', 'b', 'c', 'd']))
+        );
+        assert_eq!(astar('a', weighted, heuristic, |&node| node == 'x'), None);
+    }
+
+    #[test]
+    fn test_all_pairs() {
+        let pairs = all_pairs(['a', 'b', 'c', 'd'], weighted);
+        assert_eq!(pairs.distance(&'d', &'a'), Some(7));
+        assert_eq!(pairs.distance(&'b', &'b'), Some(0));
+        assert_eq!(pairs.path(&'d', &'b'), Some(vec!['d', 'c', 'b']));
+        assert_eq!(pairs.distance(&'x', &'a'), None);
+        assert_eq!(pairs.from(&'c').and_then(|s| s.distance(&'a')), Some(2));
+    }
+}